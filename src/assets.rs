@@ -0,0 +1,156 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoadError, AssetLoader, LoadContext, LoadState};
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Registers `A` as an asset that is loaded from RON files with one of the given extensions.
+pub struct RonAssetPlugin<A> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<A>,
+}
+
+impl<A> RonAssetPlugin<A> {
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        RonAssetPlugin {
+            extensions,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A: Asset + DeserializeOwned> Plugin for RonAssetPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<A>()
+            .init_resource::<DataAssets>()
+            .register_asset_loader(RonAssetLoader::<A> {
+                extensions: self.extensions,
+                _marker: PhantomData,
+            });
+    }
+}
+
+/// Loads the asset at `path` on startup and copies it into the `A` resource whenever it
+/// (re)loads. The resource has to be inserted up front, it is used until the file has loaded.
+pub struct AssetResourcePlugin<A> {
+    path: &'static str,
+    _marker: PhantomData<A>,
+}
+
+impl<A> AssetResourcePlugin<A> {
+    pub fn new(path: &'static str) -> Self {
+        AssetResourcePlugin {
+            path,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A: Asset + Resource + Clone> Plugin for AssetResourcePlugin<A> {
+    fn build(&self, app: &mut App) {
+        let path = self.path;
+        app.init_resource::<DataAssets>()
+            .add_systems(
+                Startup,
+                move |mut commands: Commands,
+                      asset_server: Res<AssetServer>,
+                      mut data_assets: ResMut<DataAssets>| {
+                    let handle = asset_server.load::<A>(path);
+                    data_assets.track(handle.clone());
+                    commands.insert_resource(AssetResourceHandle(handle));
+                },
+            )
+            .add_systems(PreUpdate, apply_asset_resource::<A>);
+    }
+}
+
+#[derive(Resource)]
+struct AssetResourceHandle<A: Asset>(Handle<A>);
+
+fn apply_asset_resource<A: Asset + Resource + Clone>(
+    mut asset_events: EventReader<AssetEvent<A>>,
+    assets: Res<Assets<A>>,
+    handle: Option<Res<AssetResourceHandle<A>>>,
+    mut resource: ResMut<A>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in asset_events.read() {
+        if (event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0))
+            && let Some(loaded) = assets.get(&handle.0)
+        {
+            info!(
+                "Applying {}",
+                handle.0.path().map_or(String::new(), ToString::to_string)
+            );
+            *resource = loaded.clone();
+        }
+    }
+}
+
+struct RonAssetLoader<A> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<A>,
+}
+
+#[derive(Debug, Error)]
+pub enum RonAssetLoaderError {
+    #[error("could not read asset: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse RON: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl<A: Asset + DeserializeOwned> AssetLoader for RonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = RonAssetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<A, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
+
+#[derive(Resource, Default)]
+/// Data files gameplay depends on, so headless runs can wait for them before the first tick.
+pub struct DataAssets(Vec<UntypedHandle>);
+
+impl DataAssets {
+    pub fn track(&mut self, handle: impl Into<UntypedHandle>) {
+        self.0.push(handle.into());
+    }
+
+    /// Whether every tracked file has either loaded or failed to load.
+    pub fn settled(&self, asset_server: &AssetServer) -> bool {
+        self.0.iter().all(|handle| {
+            matches!(
+                asset_server.load_state(handle.id()),
+                LoadState::Loaded | LoadState::Failed(_)
+            )
+        })
+    }
+
+    /// Why a tracked file failed to load, if one did.
+    pub fn load_error(&self, asset_server: &AssetServer) -> Option<Arc<AssetLoadError>> {
+        self.0
+            .iter()
+            .find_map(|handle| match asset_server.load_state(handle.id()) {
+                LoadState::Failed(error) => Some(error),
+                _ => None,
+            })
+    }
+}
@@ -0,0 +1,239 @@
+//! Runs the game without a window so it can be simulated on machines without a display.
+//!
+//! ```text
+//! creeps-sim [--seed <n>] [--runs <n>] [--max-ticks <n>] [--bot idle|random|dodge]
+//!            [--mode classic|gauntlet|casual|onslaught] [--players <n>]
+//!            [--ship standard|dart|brick|drifter] [--waves <file>|none]
+//!            [--replay <file>] [--save-replays]
+//! ```
+use bevy::prelude::*;
+use bevy_creeps::gameplay::enemies::Enemy;
+use bevy_creeps::gameplay::input::{PlayerInput, PlayerInputSet};
+use bevy_creeps::gameplay::mode::GameMode;
+use bevy_creeps::gameplay::player::{MAX_PLAYERS, Player, PlayerCount};
+use bevy_creeps::gameplay::replay::{Replay, SaveReplays};
+use bevy_creeps::gameplay::ships::{Ship, ShipSelection};
+use bevy_creeps::sim::{GameSim, GameSimError};
+use bevy_creeps::world::State;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// One hour of play at the default fixed timestep.
+const DEFAULT_MAX_TICKS: u32 = 64 * 60 * 60;
+
+#[derive(Resource, Clone, Copy, Debug)]
+enum Bot {
+    /// Never moves.
+    Idle,
+    /// Picks a new random direction every half second.
+    Random,
+    /// Moves away from nearby enemies while staying close to the center.
+    Dodge,
+}
+
+#[derive(Resource)]
+/// Randomness for the bot, kept apart from the gameplay randomness so bots do not change the run.
+struct BotRng(ChaCha8Rng);
+
+struct Options {
+    seed: Option<u64>,
+    runs: u32,
+    max_ticks: u32,
+    bot: Bot,
+    mode: GameMode,
+    players: usize,
+    ships: ShipSelection,
+    /// Wave script relative to `assets/`, `Some(None)` plays without one.
+    waves: Option<Option<String>>,
+    replay: Option<Replay>,
+    save_replays: bool,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for run in 0..options.runs {
+        let seed = options.seed.map(|seed| seed + run as u64);
+        if let Err(error) = simulate(&options, seed) {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        seed: None,
+        runs: 1,
+        max_ticks: DEFAULT_MAX_TICKS,
+        bot: Bot::Dodge,
+        mode: GameMode::Classic,
+        players: 1,
+        ships: ShipSelection::default(),
+        waves: None,
+        replay: None,
+        save_replays: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--seed" => options.seed = Some(parse_number(&value()?)?),
+            "--runs" => options.runs = parse_number(&value()?)?,
+            "--max-ticks" => options.max_ticks = parse_number(&value()?)?,
+            "--bot" => {
+                options.bot = match value()?.as_str() {
+                    "idle" => Bot::Idle,
+                    "random" => Bot::Random,
+                    "dodge" => Bot::Dodge,
+                    bot => return Err(format!("Unknown bot {bot}")),
+                }
+            }
+            "--mode" => {
+                options.mode = match value()?.as_str() {
+                    "classic" => GameMode::Classic,
+                    "gauntlet" => GameMode::Gauntlet,
+                    "casual" => GameMode::Casual,
+                    "onslaught" => GameMode::Onslaught,
+                    mode => return Err(format!("Unknown mode {mode}")),
+                }
+            }
+            "--players" => {
+                options.players = parse_number(&value()?)?;
+                if !PlayerCount(options.players).is_valid() {
+                    return Err(format!("A run has 1 to {MAX_PLAYERS} players"));
+                }
+            }
+            "--ship" => {
+                let ship = value()?;
+                let ship = Ship::ALL
+                    .into_iter()
+                    .find(|known| known.name().eq_ignore_ascii_case(&ship))
+                    .ok_or(format!("Unknown ship {ship}"))?;
+                options.ships = ShipSelection([ship; MAX_PLAYERS]);
+            }
+            "--waves" => {
+                options.waves = Some(match value()?.as_str() {
+                    "none" => None,
+                    path => Some(path.to_string()),
+                })
+            }
+            "--replay" => {
+                let path = value()?;
+                let replay = Replay::load(Path::new(&path))
+                    .map_err(|error| format!("Could not load replay {path}: {error}"))?;
+                options.seed = Some(replay.seed);
+                options.mode = replay.mode;
+                options.players = replay.players;
+                options.ships = replay.ships;
+                options.replay = Some(replay);
+            }
+            "--save-replays" => options.save_replays = true,
+            _ => return Err(format!("Unknown argument {arg}")),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{value} is not a number"))
+}
+
+fn simulate(options: &Options, seed: Option<u64>) -> Result<(), GameSimError> {
+    let mut builder = GameSim::builder()
+        .mode(options.mode)
+        .players(options.players)
+        .ships(options.ships);
+    if let Some(seed) = seed {
+        builder = builder.seed(seed);
+    }
+    if let Some(waves) = &options.waves {
+        builder = builder.wave_script(waves.as_deref());
+    }
+    if let Some(replay) = &options.replay {
+        builder = builder.replay(replay.clone());
+    }
+    let mut sim = builder.build()?;
+    let seed = sim.seed();
+
+    let app = sim.app_mut();
+    app.insert_resource(SaveReplays(options.save_replays))
+        .insert_resource(options.bot)
+        .insert_resource(BotRng(ChaCha8Rng::seed_from_u64(seed)))
+        .add_systems(
+            FixedUpdate,
+            drive_bot
+                .in_set(PlayerInputSet::Read)
+                .run_if(in_state(State::Playing)),
+        );
+
+    let mut ticks = 0;
+    while ticks < options.max_ticks && sim.state() != State::GameOver {
+        sim.step(1);
+        ticks += 1;
+    }
+
+    let cause = match sim.cause_of_death() {
+        Some(enemy) => format!("{enemy:?}"),
+        None => String::from("survived"),
+    };
+    println!(
+        "seed={} mode={:?} players={} ticks={} score={} cause={}",
+        seed,
+        sim.mode(),
+        options.players,
+        ticks,
+        sim.score(),
+        cause
+    );
+    Ok(())
+}
+
+fn drive_bot(
+    bot: Res<Bot>,
+    mut rng: ResMut<BotRng>,
+    mut tick: Local<u32>,
+    mut players: Query<(&Player, &Transform, &mut PlayerInput)>,
+    enemies: Query<&Transform, With<Enemy>>,
+) {
+    *tick += 1;
+
+    // Each player gets a bot of its own, driven in a fixed order to keep runs reproducible.
+    let mut players: Vec<_> = players.iter_mut().collect();
+    players.sort_by_key(|(player, _, _)| player.index);
+    for (_, player_transform, mut input) in players {
+        match *bot {
+            Bot::Idle => input.movement = Vec2::ZERO,
+            Bot::Random => {
+                if *tick % 32 == 1 {
+                    let angle = rng.0.random_range(0.0..std::f32::consts::TAU);
+                    input.movement = Vec2::from_angle(angle);
+                }
+            }
+            Bot::Dodge => {
+                let position = player_transform.translation.xy();
+                let mut push = -position * 0.001;
+                for enemy_transform in enemies.iter() {
+                    let away = position - enemy_transform.translation.xy();
+                    let distance = away.length().max(1.0);
+                    if distance < 250.0 {
+                        push += away / (distance * distance);
+                    }
+                }
+                input.movement = push.normalize_or_zero();
+            }
+        }
+    }
+}
@@ -1,9 +1,18 @@
+use crate::world::State;
 use bevy::prelude::*;
 
-mod enemies;
+pub mod enemies;
+pub mod high_scores;
+pub mod input;
+pub mod mode;
 mod movement;
 pub mod player;
+pub mod power_ups;
+pub mod replay;
+pub mod rng;
 pub mod score;
+pub mod ships;
+pub mod shockwave;
 
 pub struct GameplayPlugin;
 
@@ -12,8 +21,55 @@ impl Plugin for GameplayPlugin {
         app.add_plugins((
             movement::MovementPlugin,
             enemies::EnemyPlugin,
+            high_scores::HighScorePlugin,
+            mode::GameModePlugin,
+            input::PlayerInputPlugin,
             player::PlayerPlugin,
+            power_ups::PowerUpPlugin,
+            replay::ReplayPlugin,
+            rng::RngPlugin,
             score::ScorePlugin,
-        ));
+            ships::ShipPlugin,
+            shockwave::ShockwavePlugin,
+        ))
+        .configure_sets(
+            FixedUpdate,
+            (
+                GameplaySet::Input,
+                GameplaySet::Player,
+                GameplaySet::PowerUps,
+                GameplaySet::Enemies,
+                movement::MovementSet,
+                GameplaySet::Collision,
+                GameplaySet::Progress,
+            )
+                .chain()
+                .run_if(in_state(State::Playing)),
+        )
+        .add_systems(OnEnter(State::Starting), start_run);
     }
 }
+
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// The stages of a [FixedUpdate] tick, which run in this order while [State::Playing], with
+/// everything moving in between [GameplaySet::Enemies] and [GameplaySet::Collision].
+/// Every gameplay system goes in one of them and is ordered against the systems of its stage
+/// that touch the same data, so a seed and the inputs play out the same run tick for tick.
+pub enum GameplaySet {
+    /// Fills in [input::PlayerInput], see [input::PlayerInputSet].
+    Input,
+    /// Players dash, steer and fire shockwaves.
+    Player,
+    /// Power-ups spawn, run out and are picked up.
+    PowerUps,
+    /// Enemies spawn, steer, shoot and fight.
+    Enemies,
+    /// Players are kept in the arena and hit by whatever they ran into.
+    Collision,
+    /// The score, and the difficulty and boss encounters that follow it.
+    Progress,
+}
+
+fn start_run(mut next_state: ResMut<NextState<State>>) {
+    next_state.set(State::Playing);
+}
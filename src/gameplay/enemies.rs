@@ -1,85 +1,158 @@
-use crate::gameplay::movement::{Acceleration, Velocity};
+use crate::gameplay::GameplaySet;
+use crate::gameplay::movement::{Acceleration, MovementScale, Orbit, Velocity};
 use crate::gameplay::player::Player;
-use crate::world::{State, WORLD_SIZE};
+use crate::world::{State, WORLD_SIZE, arena_boundary};
 use bevy::prelude::*;
-use bevy::utils::HashMap;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::f32::consts::TAU;
 use std::time::Duration;
 
-use super::score::ScoreIncreasedEvent;
+use super::rng::EnemyRng;
+
+mod bosses;
+mod definitions;
+mod difficulty;
+mod lasers;
+mod waves;
+pub use bosses::{Boss, BossDefeated, BossEncounter};
+pub use definitions::{
+    Arming, BossDefinition, BossPhase, EnemyDefinition, EnemyDefinitions, Homing, Orbiting,
+    Shooting, ShotPattern, Splitting, Sweeping,
+};
+pub use difficulty::{DifficultyCurve, Keyframes, Progress};
+pub use lasers::LaserBeam;
+pub use waves::{Placement, Spacing, WaveDirector, WaveEvent, WaveScript, WaveScriptPath};
 
 pub const ENEMY_SPAWN_RADIUS: f32 = WORLD_SIZE + 10.0;
 pub const ENEMY_DESPAWN_RADIUS: f32 = ENEMY_SPAWN_RADIUS + 1.0;
 
 const UP: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+/// Alpha of an enemy while it is [Unarmed].
+const UNARMED_ALPHA: f32 = 0.25;
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         let initial_time_between_spawns = Duration::from_secs_f32(2.0);
-        app.insert_resource(EnemySpawnTimer(Timer::new(
+        app.add_plugins((
+            bosses::BossPlugin,
+            definitions::EnemyDefinitionsPlugin,
+            difficulty::DifficultyPlugin,
+            lasers::LaserPlugin,
+            waves::WavePlugin,
+        ))
+        .insert_resource(EnemySpawnTimer(Timer::new(
             initial_time_between_spawns,
             TimerMode::Repeating,
         )))
         .insert_resource(SpawnParameters::default())
+        .insert_resource(RandomSpawning(true))
+        .add_event::<EnemyDestroyed>()
         .add_systems(
             FixedUpdate,
             (
-                spawn_enemies,
+                spawn_enemies
+                    .run_if(
+                        |random_spawning: Res<RandomSpawning>, director: Res<WaveDirector>| {
+                            random_spawning.0 && director.allows_random_spawning()
+                        },
+                    )
+                    .run_if(not(any_with_component::<Boss>)),
                 despawn_out_of_bounds_enemies,
                 handle_shooting,
+                handle_splitting,
+                handle_mines,
                 handle_heatseeker_acceleration,
+                handle_orbit_centers,
                 handle_heatseeker_destruction,
-                increase_difficulty,
             )
-                .run_if(in_state(State::Playing)),
+                .in_set(EnemySet::Common),
         )
-        .add_systems(
-            OnEnter(State::Playing),
-            (despawn_all_enemies, reset_difficulty),
-        );
+        .configure_sets(
+            FixedUpdate,
+            (EnemySet::Common, EnemySet::Lasers, EnemySet::Bosses)
+                .chain()
+                .in_set(GameplaySet::Enemies),
+        )
+        .add_systems(OnEnter(State::Starting), despawn_all_enemies)
+        .add_systems(OnEnter(State::MainMenu), despawn_all_enemies);
     }
 }
 
-#[derive(Component, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// The enemy systems within [GameplaySet::Enemies], which run in this order.
+enum EnemySet {
+    /// Spawning and what every kind of enemy does.
+    Common,
+    Lasers,
+    Bosses,
+}
+
+#[derive(
+    Component, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug, Serialize, Deserialize,
+)]
 pub enum Enemy {
     Standard,
     Bullet,
     Cannon,
     Rocket,
     RocketShip,
+    /// Breaks up into [Enemy::Shard]s.
+    Splitter,
+    Shard,
+    /// Sweeps a [LaserBeam] across the arena.
+    LaserSweeper,
+    /// Leaves [Enemy::Mine]s behind on its way across the arena.
+    MineLayer,
+    Mine,
+    /// Circles the player ever closer.
+    Orbiter,
+    Boss,
 }
 
-impl Enemy {
-    pub fn size(&self) -> f32 {
-        match self {
-            Enemy::Bullet | Enemy::Rocket => 10.0,
-            Enemy::Cannon | Enemy::RocketShip => 40.0,
-            _ => 20.0,
-        }
-    }
+#[derive(Event, Debug)]
+/// The player destroyed an enemy, which was despawned at `position`.
+/// Enemies that leave the arena or run into the player are not destroyed.
+pub struct EnemyDestroyed {
+    /// [Player::index] of the player that destroyed it.
+    pub player: usize,
+    pub enemy: Enemy,
+    pub position: Vec3,
+}
 
-    pub fn color(&self) -> Color {
-        match self {
-            Enemy::Bullet => Color::srgb(5.0, 2.5, 0.0),
-            Enemy::Cannon => Color::srgb(2.5, 0.0, 5.0),
-            Enemy::Rocket => Color::srgb(0.0, 5.0, 0.0),
-            Enemy::RocketShip => Color::srgb(0.0, 2.5, 5.0),
-            _ => Color::srgb(5.0, 0.0, 0.0),
-        }
-    }
-    pub fn speed(&self) -> f32 {
-        match self {
-            Enemy::Bullet | Enemy::Rocket => 450.0,
-            Enemy::Cannon | Enemy::RocketShip => 200.0,
-            _ => 300.0,
-        }
-    }
+#[derive(Component)]
+/// Size of the collision box, fixed when the enemy is spawned.
+pub struct Hitbox(pub Vec2);
+
+#[derive(Component)]
+struct Shooter {
+    timer: Timer,
+    shooting: Shooting,
 }
 
 #[derive(Component)]
-struct ShootTimer(Timer);
+struct Splitter {
+    timer: Timer,
+    splitting: Splitting,
+}
+
+#[derive(Component)]
+/// Stays where it is until its lifetime is over, see [Arming].
+struct Mine {
+    arming: Timer,
+    lifetime: Timer,
+    color: Color,
+}
+
+#[derive(Component)]
+/// The enemy can not hit the player yet.
+pub struct Unarmed;
+
+#[derive(Component)]
+/// Its [Orbit] follows the closest player.
+struct Orbiter;
 
 #[derive(Component)]
 struct HeatSeeker {
@@ -89,89 +162,125 @@ struct HeatSeeker {
 #[derive(Resource)]
 struct EnemySpawnTimer(Timer);
 
+#[derive(Resource)]
+/// Whether enemies are spawned at random on the [EnemySpawnTimer].
+/// The [WaveScript] that is playing can turn random spawning off as well, and it pauses
+/// while a [Boss] is alive.
+pub struct RandomSpawning(pub bool);
+
 #[derive(Resource)]
 /// Parameters for generic spawning (so not shooting).
-/// This is what determines the difficulty along with [SpawnTable].
+/// Follows the [DifficultyCurve] over the course of a run.
 struct SpawnParameters {
     time_between_spawns: Duration,
-    min_time_between_spawns: Duration,
     probability_spawn_another: f64,
-    max_probability_spawn_another: f64,
     max_spawns: u32,
-    spawn_table: HashMap<Enemy, u32>,
+    spawn_table: BTreeMap<Enemy, u32>,
 }
 
 impl SpawnParameters {
-    fn draw_enemy(&self) -> Enemy {
+    fn draw_enemy(&self, rng: &mut impl Rng) -> Enemy {
         let totalweight = self.spawn_table.values().sum();
-        let randomweight = rand::thread_rng().gen_range(1..=totalweight);
+        if totalweight == 0 {
+            return Enemy::Standard;
+        }
+        let randomweight = rng.random_range(1..=totalweight);
         let mut weightsum = 0;
-        let mut iter = self.spawn_table.iter();
-        while let Some((enemy, weight)) = iter.next() {
+        for (enemy, weight) in self.spawn_table.iter() {
             weightsum += *weight;
             if weightsum >= randomweight {
                 return *enemy;
             }
         }
         eprintln!("Could not draw enemy! Just spawning a standard enemy.");
-        return Enemy::Standard;
+        Enemy::Standard
     }
 }
 
-impl Default for SpawnParameters {
-    fn default() -> Self {
-        let mut spawn_parameters = SpawnParameters {
-            time_between_spawns: Duration::from_secs(2),
-            min_time_between_spawns: Duration::from_millis(300),
-            max_spawns: 1,
-            probability_spawn_another: 0.0,
-            max_probability_spawn_another: 0.8,
-            spawn_table: HashMap::new(),
-        };
-        spawn_parameters.spawn_table.insert(Enemy::Standard, 25);
-        spawn_parameters
+pub fn spawn_single_enemy(
+    enemy: Enemy,
+    position: Vec3,
+    direction: Vec3,
+    definitions: &EnemyDefinitions,
+    commands: &mut Commands,
+) {
+    let definition = definitions.get(enemy);
+    let size = Vec2::splat(definition.size);
+    let velocity = direction * definition.speed;
+
+    let color = if definition.arming.is_some() {
+        definition.color.with_alpha(UNARMED_ALPHA)
+    } else {
+        definition.color
+    };
+
+    let mut entity = commands.spawn((
+        Sprite::from_color(color, size),
+        Transform::from_translation(position),
+        Velocity::new(velocity, velocity.length()),
+        Hitbox(size),
+        enemy,
+    ));
+
+    if let Some(shooting) = definition.shooting {
+        entity.insert(Shooter {
+            timer: Timer::from_seconds(shooting.interval, TimerMode::Repeating),
+            shooting,
+        });
     }
-}
 
-fn spawn_single_enemy(enemy: Enemy, position: Vec3, direction: Vec3, commands: &mut Commands) {
-    let size = enemy.size();
-    let speed = enemy.speed();
-    let color = enemy.color();
+    if let Some(splitting) = definition.splitting {
+        entity.insert(Splitter {
+            timer: Timer::from_seconds(splitting.delay, TimerMode::Once),
+            splitting,
+        });
+    }
 
-    let velocity = direction * speed;
+    if let Some(sweeping) = definition.sweeping {
+        entity.insert(lasers::LaserSweeper::new(
+            sweeping,
+            definition.color,
+            definition.speed,
+        ));
+    }
 
-    let entity = commands
-        .spawn((
-            Sprite::from_color(color, Vec2::new(size, size)),
-            Transform::from_translation(position),
-            Velocity::new(velocity, velocity.length()),
-            enemy,
-        ))
-        .id();
+    if let Some(arming) = definition.arming {
+        entity.insert((
+            Mine {
+                arming: Timer::from_seconds(arming.delay, TimerMode::Once),
+                lifetime: Timer::from_seconds(arming.lifetime, TimerMode::Once),
+                color: definition.color,
+            },
+            Unarmed,
+        ));
+    }
 
-    match enemy {
-        Enemy::Cannon => {
-            commands
-                .entity(entity)
-                .insert(ShootTimer(Timer::from_seconds(2.0, TimerMode::Repeating)));
-        }
-        Enemy::RocketShip => {
-            commands
-                .entity(entity)
-                .insert(ShootTimer(Timer::from_seconds(3.0, TimerMode::Repeating)));
-        }
-        Enemy::Rocket => {
-            commands.entity(entity).insert((
-                HeatSeeker {
-                    alive_timer: Timer::new(Duration::from_secs(5), TimerMode::Once),
-                },
-                Acceleration {
-                    direction: Vec3::ZERO,
-                    amount: 1.0,
-                },
-            ));
-        }
-        _ => {}
+    if let Some(orbiting) = definition.orbiting {
+        entity.insert((
+            Orbiter,
+            Orbit {
+                center: Vec3::ZERO,
+                radius: orbiting.radius,
+                angular_speed: orbiting.angular_speed.to_radians(),
+                decay: orbiting.decay,
+            },
+        ));
+    }
+
+    if let Some(boss) = &definition.boss {
+        entity.insert(bosses::Boss::new(boss.clone()));
+    }
+
+    if let Some(homing) = definition.homing {
+        entity.insert((
+            HeatSeeker {
+                alive_timer: Timer::from_seconds(homing.lifetime, TimerMode::Once),
+            },
+            Acceleration {
+                direction: Vec3::ZERO,
+                amount: homing.acceleration,
+            },
+        ));
     }
 }
 
@@ -179,29 +288,30 @@ fn spawn_enemies(
     mut commands: Commands,
     mut timer: ResMut<EnemySpawnTimer>,
     spawn_parameters: Res<SpawnParameters>,
+    definitions: Res<EnemyDefinitions>,
+    mut rng: ResMut<EnemyRng>,
     time: Res<Time>,
 ) {
     if timer.0.tick(time.delta()).finished() {
-        let mut random = rand::thread_rng();
-
         let mut spawned = 0;
         while spawned < spawn_parameters.max_spawns {
-            let spawn_angle: f32 = random.gen_range(0.0..TAU);
+            let spawn_angle: f32 = rng.random_range(0.0..TAU);
             let position = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), spawn_angle)
                 .mul_vec3(UP)
                 * ENEMY_SPAWN_RADIUS;
 
-            let movement_angle = spawn_angle + random.gen_range(-0.3..0.3);
+            let movement_angle = spawn_angle + rng.random_range(-0.3..0.3);
             let direction = Quat::from_axis_angle(Vec3::Z, movement_angle).mul_vec3(Vec3::NEG_Y);
 
             spawn_single_enemy(
-                spawn_parameters.draw_enemy(),
+                spawn_parameters.draw_enemy(&mut *rng),
                 position,
                 direction,
+                &definitions,
                 &mut commands,
             );
 
-            if random.gen_bool(1.0 - spawn_parameters.probability_spawn_another) {
+            if rng.random_bool(1.0 - spawn_parameters.probability_spawn_another) {
                 break;
             }
 
@@ -214,65 +324,168 @@ fn spawn_enemies(
 
 fn handle_shooting(
     mut commands: Commands,
-    mut query: Query<(&Transform, &mut ShootTimer, &Enemy)>,
-    player_transform_query: Option<Single<&GlobalTransform, With<Player>>>, // This limits parallelization and is only needed for rocketship so maybe change
+    mut query: Query<(&Transform, &mut Shooter)>,
+    players: Query<&Transform, With<Player>>,
+    definitions: Res<EnemyDefinitions>,
     time: Res<Time>,
 ) {
-    for (transform, mut timer, enemy) in query.iter_mut() {
-        if timer.0.tick(time.delta()).finished() {
-            match enemy {
-                Enemy::Cannon => {
-                    for i in 0..12 {
-                        let movement_angle = i as f32 * (TAU / 12.0 as f32);
-                        let direction =
-                            Quat::from_axis_angle(Vec3::Z, movement_angle).mul_vec3(Vec3::NEG_Y);
-                        spawn_single_enemy(
-                            Enemy::Bullet,
-                            transform.translation,
-                            direction,
-                            &mut commands,
-                        );
-                    }
-                }
-                Enemy::RocketShip => {
-                    let direction = match player_transform_query {
-                        Some(ref player_transform) => transform
-                            .looking_at(player_transform.translation(), Vec3::Y)
-                            .forward()
-                            .normalize_or(Vec3::Y),
-                        None => Vec3::Y,
-                    };
-                    spawn_single_enemy(
-                        Enemy::Rocket,
-                        transform.translation,
-                        direction,
-                        &mut commands,
-                    );
-                }
-                _ => {}
+    for (transform, mut shooter) in query.iter_mut() {
+        if shooter.timer.tick(time.delta()).finished() {
+            shoot(
+                &shooter.shooting,
+                transform,
+                &players,
+                &definitions,
+                &mut commands,
+            );
+        }
+    }
+}
+
+/// Fires a single round of `shooting` from the shooter at `transform`.
+fn shoot(
+    shooting: &Shooting,
+    transform: &Transform,
+    players: &Query<&Transform, With<Player>>,
+    definitions: &EnemyDefinitions,
+    commands: &mut Commands,
+) {
+    let projectile = shooting.projectile;
+    match shooting.pattern {
+        ShotPattern::Radial { count } => {
+            for i in 0..count {
+                let movement_angle = i as f32 * (TAU / count as f32);
+                let direction =
+                    Quat::from_axis_angle(Vec3::Z, movement_angle).mul_vec3(Vec3::NEG_Y);
+                spawn_single_enemy(
+                    projectile,
+                    transform.translation,
+                    direction,
+                    definitions,
+                    commands,
+                );
             }
         }
+        ShotPattern::Drop => {
+            if transform.translation.abs().cmple(arena_boundary(0.0)).all() {
+                spawn_single_enemy(
+                    projectile,
+                    transform.translation,
+                    Vec3::ZERO,
+                    definitions,
+                    commands,
+                );
+            }
+        }
+        ShotPattern::AtPlayer => {
+            let direction = match nearest_player(transform.translation, players) {
+                Some(player_position) => transform
+                    .looking_at(player_position, Vec3::Y)
+                    .forward()
+                    .normalize_or(Vec3::Y),
+                None => Vec3::Y,
+            };
+            spawn_single_enemy(
+                projectile,
+                transform.translation,
+                direction,
+                definitions,
+                commands,
+            );
+        }
     }
 }
 
-fn handle_heatseeker_acceleration(
-    mut heatseeker_accelerations: Query<(&Transform, &mut Acceleration), With<HeatSeeker>>,
-    player_transform: Option<Single<&Transform, With<Player>>>,
+fn handle_splitting(
+    mut commands: Commands,
+    mut splitters: Query<(Entity, &Transform, &Velocity, &mut Splitter)>,
+    definitions: Res<EnemyDefinitions>,
+    time: Res<Time>,
+) {
+    for (entity, transform, velocity, mut splitter) in splitters.iter_mut() {
+        let splitting = splitter.splitting;
+        let timed_out = splitter.timer.tick(time.delta()).finished();
+        let at_center = transform.translation.length() <= splitting.center_radius;
+        if !timed_out && !at_center {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        let heading = velocity.value.try_normalize().unwrap_or(Vec3::NEG_Y);
+        let spread = splitting.spread.to_radians();
+        for i in 0..splitting.count {
+            let angle = if splitting.count > 1 {
+                -0.5 * spread + spread * i as f32 / (splitting.count - 1) as f32
+            } else {
+                0.0
+            };
+            spawn_single_enemy(
+                splitting.fragment,
+                transform.translation,
+                Quat::from_rotation_z(angle).mul_vec3(heading),
+                &definitions,
+                &mut commands,
+            );
+        }
+    }
+}
+
+/// Arms mines once their delay is over and removes them at the end of their lifetime.
+fn handle_mines(
+    mut commands: Commands,
+    mut mines: Query<(Entity, &mut Mine, &mut Sprite, Option<&MovementScale>)>,
+    time: Res<Time>,
 ) {
-    if player_transform.is_none() {
-        return;
+    for (entity, mut mine, mut sprite, scale) in mines.iter_mut() {
+        let delta = time.delta().mul_f32(scale.map_or(1.0, |scale| scale.0));
+        if mine.lifetime.tick(delta).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if mine.arming.tick(delta).just_finished() {
+            sprite.color = mine.color;
+            commands.entity(entity).remove::<Unarmed>();
+        }
     }
+}
 
-    let player_transform = player_transform.unwrap();
+/// Position of the living player closest to `position`, `None` once every player is out.
+fn nearest_player(position: Vec3, players: &Query<&Transform, With<Player>>) -> Option<Vec3> {
+    players
+        .iter()
+        .map(|transform| transform.translation)
+        .min_by(|a, b| {
+            a.distance_squared(position)
+                .total_cmp(&b.distance_squared(position))
+        })
+}
 
+fn handle_heatseeker_acceleration(
+    mut heatseeker_accelerations: Query<(&Transform, &mut Acceleration), With<HeatSeeker>>,
+    players: Query<&Transform, With<Player>>,
+) {
     for (transform, mut acceleration) in heatseeker_accelerations.iter_mut() {
+        let Some(player_position) = nearest_player(transform.translation, &players) else {
+            return;
+        };
         acceleration.direction = transform
-            .looking_at(player_transform.translation, Vec3::Y)
+            .looking_at(player_position, Vec3::Y)
             .forward()
             .into();
     }
 }
 
+fn handle_orbit_centers(
+    mut orbits: Query<(&Transform, &mut Orbit), With<Orbiter>>,
+    players: Query<&Transform, With<Player>>,
+) {
+    for (transform, mut orbit) in orbits.iter_mut() {
+        if let Some(player_position) = nearest_player(transform.translation, &players) {
+            orbit.center = player_position;
+        }
+    }
+}
+
 fn handle_heatseeker_destruction(
     mut heatseekers: Query<(Entity, &mut HeatSeeker)>,
     mut commands: Commands,
@@ -285,40 +498,6 @@ fn handle_heatseeker_destruction(
     }
 }
 
-fn increase_difficulty(
-    mut spawn_parameters: ResMut<SpawnParameters>,
-    mut score_increased_event: EventReader<ScoreIncreasedEvent>,
-) {
-    for event in score_increased_event.read() {
-        let new_score = event.get_new_score();
-        spawn_parameters.probability_spawn_another = spawn_parameters
-            .max_probability_spawn_another
-            .min(spawn_parameters.probability_spawn_another + 0.03);
-        spawn_parameters.time_between_spawns = spawn_parameters
-            .min_time_between_spawns
-            .max(spawn_parameters.time_between_spawns - Duration::from_millis(10));
-
-        spawn_parameters.max_spawns = 1 + new_score / 30;
-
-        spawn_parameters
-            .spawn_table
-            .insert(Enemy::Cannon, new_score / 10);
-
-        println!(
-            "Cannon weight: {}",
-            spawn_parameters.spawn_table.get(&Enemy::Cannon).unwrap()
-        );
-
-        spawn_parameters
-            .spawn_table
-            .insert(Enemy::RocketShip, new_score / 15);
-    }
-}
-
-fn reset_difficulty(mut spawn_parameters: ResMut<SpawnParameters>) {
-    *spawn_parameters = SpawnParameters::default();
-}
-
 fn despawn_out_of_bounds_enemies(
     mut commands: Commands,
     query: Query<(Entity, &Transform), With<Enemy>>,
@@ -0,0 +1,198 @@
+use super::{
+    BossDefinition, DifficultyCurve, ENEMY_SPAWN_RADIUS, Enemy, EnemyDefinitions, EnemySet, Hitbox,
+    shoot, spawn_single_enemy,
+};
+use crate::gameplay::GameplaySet;
+use crate::gameplay::movement::Velocity;
+use crate::gameplay::player::Player;
+use crate::gameplay::score::{Score, ScoreSet};
+use crate::world::{State, arena_boundary};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NextBossEncounter>()
+            .add_event::<BossDefeated>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    (park_bosses, fight_bosses).chain().in_set(EnemySet::Bosses),
+                    start_boss_encounters
+                        .after(ScoreSet)
+                        .in_set(GameplaySet::Progress),
+                ),
+            )
+            .add_systems(OnEnter(State::Starting), reset_boss_encounters);
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+/// Spawns `boss` once the [Score] of the run reaches `score`.
+pub struct BossEncounter {
+    pub score: u32,
+    pub boss: Enemy,
+}
+
+#[derive(Event, Debug)]
+/// The players survived a boss or wore it down, it was despawned.
+pub struct BossDefeated {
+    pub boss: Enemy,
+    pub bonus: u32,
+}
+
+#[derive(Resource, Default)]
+/// Index of the next [BossEncounter] in the [DifficultyCurve].
+struct NextBossEncounter(usize);
+
+#[derive(Component)]
+/// An enemy with a [BossDefinition]. Random spawning pauses while it is alive, and it is
+/// not used up when it runs into a player.
+pub struct Boss {
+    definition: BossDefinition,
+    health: u32,
+    time_limit: Timer,
+    phase: usize,
+    phase_timer: Timer,
+    /// One per attack of the current phase.
+    attack_timers: Vec<Timer>,
+    parked: bool,
+}
+
+impl Boss {
+    pub(super) fn new(definition: BossDefinition) -> Self {
+        let mut boss = Boss {
+            health: definition.health,
+            time_limit: Timer::from_seconds(definition.time_limit, TimerMode::Once),
+            phase: 0,
+            phase_timer: Timer::default(),
+            attack_timers: Vec::new(),
+            definition,
+            parked: false,
+        };
+        boss.start_phase(0);
+        boss
+    }
+
+    fn start_phase(&mut self, phase: usize) {
+        self.phase = phase;
+        let Some(phase) = self.definition.phases.get(phase) else {
+            return;
+        };
+        self.phase_timer = Timer::from_seconds(phase.duration, TimerMode::Once);
+        self.attack_timers = phase
+            .attacks
+            .iter()
+            .map(|attack| Timer::from_seconds(attack.interval, TimerMode::Repeating))
+            .collect();
+    }
+
+    /// Takes one hit off the health of the boss, for shockwaves and bombs.
+    pub fn hit(&mut self) {
+        self.health = self.health.saturating_sub(1);
+    }
+
+    /// From 1 at full health down to 0.
+    pub fn health_fraction(&self) -> f32 {
+        self.health as f32 / self.definition.health.max(1) as f32
+    }
+
+    /// Seconds the players still have to survive the boss.
+    pub fn remaining_secs(&self) -> f32 {
+        self.time_limit.remaining_secs()
+    }
+
+    fn is_defeated(&self) -> bool {
+        self.health == 0 || self.time_limit.finished()
+    }
+}
+
+fn reset_boss_encounters(mut next: ResMut<NextBossEncounter>) {
+    next.0 = 0;
+}
+
+/// Spawns the next boss above the arena once the score reaches it, one boss at a time.
+fn start_boss_encounters(
+    mut commands: Commands,
+    mut next: ResMut<NextBossEncounter>,
+    bosses: Query<(), With<Boss>>,
+    curve: Res<DifficultyCurve>,
+    definitions: Res<EnemyDefinitions>,
+    score: Res<Score>,
+) {
+    if !bosses.is_empty() {
+        return;
+    }
+    let Some(encounter) = curve.bosses.get(next.0) else {
+        return;
+    };
+    if score.0 < encounter.score {
+        return;
+    }
+    next.0 += 1;
+    spawn_single_enemy(
+        encounter.boss,
+        Vec3::Y * ENEMY_SPAWN_RADIUS,
+        Vec3::NEG_Y,
+        &definitions,
+        &mut commands,
+    );
+}
+
+/// Stops bosses once they are fully inside the arena.
+fn park_bosses(mut bosses: Query<(&Transform, &Hitbox, &mut Velocity, &mut Boss)>) {
+    for (transform, hitbox, mut velocity, mut boss) in bosses.iter_mut() {
+        let inside = transform
+            .translation
+            .abs()
+            .cmple(arena_boundary(hitbox.0.x))
+            .all();
+        if !boss.parked && inside {
+            boss.parked = true;
+            velocity.value = Vec3::ZERO;
+        }
+    }
+}
+
+/// Runs the time limit and attacks of parked bosses, and despawns the defeated ones.
+fn fight_bosses(
+    mut commands: Commands,
+    mut defeated_events: EventWriter<BossDefeated>,
+    mut bosses: Query<(Entity, &Transform, &Enemy, &mut Boss)>,
+    players: Query<&Transform, With<Player>>,
+    definitions: Res<EnemyDefinitions>,
+    time: Res<Time>,
+) {
+    for (entity, transform, enemy, mut boss) in bosses.iter_mut() {
+        if boss.parked {
+            boss.time_limit.tick(time.delta());
+        }
+        if boss.is_defeated() {
+            commands.entity(entity).despawn();
+            defeated_events.send(BossDefeated {
+                boss: *enemy,
+                bonus: boss.definition.bonus,
+            });
+            continue;
+        }
+        if !boss.parked {
+            continue;
+        }
+
+        if boss.phase_timer.tick(time.delta()).finished() {
+            let next_phase = (boss.phase + 1) % boss.definition.phases.len().max(1);
+            boss.start_phase(next_phase);
+        }
+        let boss = &mut *boss;
+        let Some(phase) = boss.definition.phases.get(boss.phase) else {
+            continue;
+        };
+        for (attack, timer) in phase.attacks.iter().zip(boss.attack_timers.iter_mut()) {
+            if timer.tick(time.delta()).finished() {
+                shoot(attack, transform, &players, &definitions, &mut commands);
+            }
+        }
+    }
+}
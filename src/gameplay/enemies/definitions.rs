@@ -0,0 +1,158 @@
+use super::Enemy;
+use crate::assets::{AssetResourcePlugin, RonAssetPlugin};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+const ENEMY_DEFINITIONS_PATH: &str = "default.enemies.ron";
+
+/// The definitions shipped with the game, used until the asset has loaded and for
+/// enemies the asset leaves out.
+static BUILTIN_DEFINITIONS: LazyLock<EnemyDefinitions> = LazyLock::new(|| {
+    ron::from_str(include_str!("../../../assets/default.enemies.ron"))
+        .expect("built-in enemy definitions should be valid")
+});
+
+pub struct EnemyDefinitionsPlugin;
+
+impl Plugin for EnemyDefinitionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BUILTIN_DEFINITIONS.clone())
+            .add_plugins((
+                RonAssetPlugin::<EnemyDefinitions>::new(&["enemies.ron"]),
+                AssetResourcePlugin::<EnemyDefinitions>::new(ENEMY_DEFINITIONS_PATH),
+            ));
+    }
+}
+
+#[derive(Asset, Resource, TypePath, Deserialize, Clone, Debug)]
+/// How every kind of [Enemy] looks and behaves.
+/// The resource holds the definitions new enemies are spawned with and follows the asset
+/// when it is (re)loaded, enemies that are already alive keep what they were spawned with.
+pub struct EnemyDefinitions {
+    enemies: HashMap<Enemy, EnemyDefinition>,
+}
+
+impl EnemyDefinitions {
+    pub fn get(&self, enemy: Enemy) -> &EnemyDefinition {
+        self.enemies
+            .get(&enemy)
+            .or_else(|| BUILTIN_DEFINITIONS.enemies.get(&enemy))
+            .unwrap_or_else(|| panic!("{enemy:?} has no built-in definition"))
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct EnemyDefinition {
+    pub size: f32,
+    pub color: Color,
+    pub speed: f32,
+    /// Awarded when the enemy is destroyed, see [EnemyDestroyed](super::EnemyDestroyed).
+    #[serde(default)]
+    pub points: u32,
+    #[serde(default)]
+    pub shooting: Option<Shooting>,
+    #[serde(default)]
+    pub homing: Option<Homing>,
+    #[serde(default)]
+    pub splitting: Option<Splitting>,
+    #[serde(default)]
+    pub sweeping: Option<Sweeping>,
+    #[serde(default)]
+    pub arming: Option<Arming>,
+    #[serde(default)]
+    pub orbiting: Option<Orbiting>,
+    #[serde(default)]
+    pub boss: Option<BossDefinition>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+/// Periodically spawns `projectile` enemies from the shooter.
+pub struct Shooting {
+    /// Seconds between shots.
+    pub interval: f32,
+    pub projectile: Enemy,
+    pub pattern: ShotPattern,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum ShotPattern {
+    /// `count` projectiles evenly spread around the shooter.
+    Radial { count: u32 },
+    /// A single projectile aimed at the player.
+    AtPlayer,
+    /// A single projectile left behind where the shooter is, only while it is inside the arena.
+    Drop,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+/// Replaces the enemy with `count` `fragment` enemies after `delay` seconds, or sooner once it
+/// comes within `center_radius` of the arena center.
+pub struct Splitting {
+    pub delay: f32,
+    pub center_radius: f32,
+    pub fragment: Enemy,
+    pub count: u32,
+    /// Angle in degrees between the outermost fragments, which fan out around the direction the
+    /// enemy was moving in.
+    pub spread: f32,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+/// Stops at the edge of the arena and sweeps a laser beam across it, then leaves.
+pub struct Sweeping {
+    /// Seconds the beam shows as a thin warning line before it fires.
+    pub warning: f32,
+    /// Seconds the firing beam takes to sweep through the `arc`.
+    pub duration: f32,
+    /// Angle in degrees the beam sweeps through, centered on the arena center.
+    pub arc: f32,
+    pub width: f32,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+/// The enemy can only hit the player `delay` seconds after it was spawned, and is gone after
+/// `lifetime` seconds.
+pub struct Arming {
+    pub delay: f32,
+    pub lifetime: f32,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+/// Circles the player closest to it, closing in until the `radius` is used up after which it
+/// flies on in a straight line.
+pub struct Orbiting {
+    pub radius: f32,
+    /// Degrees per second.
+    pub angular_speed: f32,
+    /// How much the radius shrinks per second.
+    pub decay: f32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+/// Makes the enemy a boss, see [Boss](super::Boss). It stops once it is inside the arena and
+/// goes through its `phases` in turn, starting over after the last one.
+pub struct BossDefinition {
+    /// Hits from shockwaves and bombs it takes to defeat the boss.
+    pub health: u32,
+    /// Seconds the players have to survive to defeat the boss.
+    pub time_limit: f32,
+    /// Added to the score of the run and of every player still in when the boss is defeated.
+    pub bonus: u32,
+    pub phases: Vec<BossPhase>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+/// `attacks` the boss fires for `duration` seconds.
+pub struct BossPhase {
+    pub duration: f32,
+    pub attacks: Vec<Shooting>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+/// Steers towards the player until `lifetime` seconds have passed.
+pub struct Homing {
+    pub acceleration: f32,
+    pub lifetime: f32,
+}
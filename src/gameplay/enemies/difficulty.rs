@@ -0,0 +1,159 @@
+use super::{BossEncounter, Enemy, EnemySpawnTimer, SpawnParameters};
+use crate::assets::{AssetResourcePlugin, RonAssetPlugin};
+use crate::gameplay::GameplaySet;
+use crate::gameplay::score::{Score, ScoreSet};
+use crate::world::State;
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+const DIFFICULTY_CURVE_PATH: &str = "default.difficulty.ron";
+
+/// The curve shipped with the game, used until the asset has loaded.
+static BUILTIN_CURVE: LazyLock<DifficultyCurve> = LazyLock::new(|| {
+    ron::from_str(include_str!("../../../assets/default.difficulty.ron"))
+        .expect("built-in difficulty curve should be valid")
+});
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BUILTIN_CURVE.clone())
+            .insert_resource(RunClock(Stopwatch::new()))
+            .add_plugins((
+                RonAssetPlugin::<DifficultyCurve>::new(&["difficulty.ron"]),
+                AssetResourcePlugin::<DifficultyCurve>::new(DIFFICULTY_CURVE_PATH),
+            ))
+            .add_systems(
+                FixedUpdate,
+                increase_difficulty
+                    .after(ScoreSet)
+                    .in_set(GameplaySet::Progress),
+            )
+            .add_systems(OnEnter(State::Starting), reset_difficulty);
+    }
+}
+
+#[derive(Asset, Resource, TypePath, Deserialize, Clone, Debug)]
+/// How the [SpawnParameters] develop over a run.
+/// Every curve is sampled at the run's progress, as measured by `progress`.
+pub struct DifficultyCurve {
+    pub progress: Progress,
+    /// Seconds between two rounds of spawning.
+    pub time_between_spawns: Keyframes,
+    /// Chance that another enemy is spawned in the same round.
+    pub probability_spawn_another: Keyframes,
+    pub max_spawns: Keyframes,
+    /// Weight of each enemy in the spawn table, enemies without a curve are never spawned.
+    pub spawn_weights: BTreeMap<Enemy, Keyframes>,
+    /// Bosses in the order they are met, by score whatever the `progress`.
+    #[serde(default)]
+    pub bosses: Vec<BossEncounter>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Progress {
+    /// The current [Score].
+    Score,
+    /// Whole seconds since the run started.
+    Seconds,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(transparent)]
+/// A piecewise-linear function through `(x, y)` keyframes sorted by `x`.
+/// It stays constant before the first and after the last keyframe.
+pub struct Keyframes(Vec<(f32, f32)>);
+
+impl Keyframes {
+    pub fn sample(&self, x: f32) -> f32 {
+        let Some(&(first_x, first_y)) = self.0.first() else {
+            return 0.0;
+        };
+        if x <= first_x {
+            return first_y;
+        }
+        for window in self.0.windows(2) {
+            let ((x0, y0), (x1, y1)) = (window[0], window[1]);
+            if x <= x1 {
+                return y0 + (y1 - y0) * (x - x0) / (x1 - x0);
+            }
+        }
+        self.0[self.0.len() - 1].1
+    }
+}
+
+impl DifficultyCurve {
+    fn spawn_parameters_at(&self, progress: f32) -> SpawnParameters {
+        SpawnParameters {
+            time_between_spawns: Duration::from_secs_f32(
+                self.time_between_spawns.sample(progress).max(0.01),
+            ),
+            probability_spawn_another: self
+                .probability_spawn_another
+                .sample(progress)
+                .clamp(0.0, 1.0) as f64,
+            max_spawns: self.max_spawns.sample(progress).max(1.0) as u32,
+            spawn_table: self
+                .spawn_weights
+                .iter()
+                .map(|(enemy, weight)| (*enemy, weight.sample(progress).max(0.0) as u32))
+                .collect(),
+        }
+    }
+}
+
+impl Default for SpawnParameters {
+    fn default() -> Self {
+        BUILTIN_CURVE.spawn_parameters_at(0.0)
+    }
+}
+
+#[derive(Resource)]
+/// Time spent in the current run, for curves that progress with [Progress::Seconds].
+struct RunClock(Stopwatch);
+
+fn increase_difficulty(
+    mut spawn_parameters: ResMut<SpawnParameters>,
+    mut clock: ResMut<RunClock>,
+    mut last_progress: Local<f32>,
+    curve: Res<DifficultyCurve>,
+    score: Res<Score>,
+    time: Res<Time>,
+) {
+    clock.0.tick(time.delta());
+    let progress = match curve.progress {
+        Progress::Score => score.0 as f32,
+        Progress::Seconds => clock.0.elapsed_secs().floor(),
+    };
+    if progress == *last_progress {
+        return;
+    }
+    *last_progress = progress;
+
+    *spawn_parameters = curve.spawn_parameters_at(progress);
+    debug!(
+        progress,
+        time_between_spawns = ?spawn_parameters.time_between_spawns,
+        probability_spawn_another = spawn_parameters.probability_spawn_another,
+        max_spawns = spawn_parameters.max_spawns,
+        spawn_table = ?spawn_parameters.spawn_table,
+        "Difficulty increased"
+    );
+}
+
+fn reset_difficulty(
+    mut spawn_parameters: ResMut<SpawnParameters>,
+    mut timer: ResMut<EnemySpawnTimer>,
+    mut clock: ResMut<RunClock>,
+    curve: Res<DifficultyCurve>,
+) {
+    *spawn_parameters = curve.spawn_parameters_at(0.0);
+    timer.0.set_duration(spawn_parameters.time_between_spawns);
+    timer.0.reset();
+    clock.0.reset();
+}
@@ -0,0 +1,176 @@
+use super::{Enemy, EnemySet, Hitbox, Sweeping};
+use crate::gameplay::movement::{MovementScale, Velocity};
+use crate::world::{State, WORLD_SIZE, arena_boundary};
+use bevy::math::bounding::{Aabb2d, BoundingVolume, RayCast2d};
+use bevy::prelude::*;
+
+/// Long enough for a beam fired from the edge to reach across the whole arena.
+const BEAM_LENGTH: f32 = 1.5 * WORLD_SIZE;
+const WARNING_LINE_WIDTH: f32 = 2.0;
+const WARNING_LINE_ALPHA: f32 = 0.3;
+
+pub struct LaserPlugin;
+
+impl Plugin for LaserPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (park_laser_sweepers, sweep_laser_beams)
+                .chain()
+                .in_set(EnemySet::Lasers),
+        )
+        .add_systems(OnEnter(State::Starting), despawn_all_beams)
+        .add_systems(OnEnter(State::MainMenu), despawn_all_beams);
+    }
+}
+
+#[derive(Component)]
+/// Moves in until it is inside the arena, stops there to fire a [LaserBeam] and leaves again
+/// once the beam is done.
+pub(super) struct LaserSweeper {
+    sweeping: Sweeping,
+    color: Color,
+    speed: f32,
+    parked: bool,
+}
+
+impl LaserSweeper {
+    pub(super) fn new(sweeping: Sweeping, color: Color, speed: f32) -> Self {
+        LaserSweeper {
+            sweeping,
+            color,
+            speed,
+            parked: false,
+        }
+    }
+}
+
+#[derive(Component)]
+/// A beam from `origin` out across the arena. It shows as a thin line during the warning and
+/// only hits players once it fires, while it sweeps through its arc.
+pub struct LaserBeam {
+    /// The [LaserSweeper] that fired it, the beam goes out when it is destroyed.
+    source: Entity,
+    enemy: Enemy,
+    origin: Vec2,
+    start_angle: f32,
+    arc: f32,
+    width: f32,
+    warning: Timer,
+    sweep: Timer,
+}
+
+impl LaserBeam {
+    /// The kind of enemy that fired the beam.
+    pub fn enemy(&self) -> Enemy {
+        self.enemy
+    }
+
+    pub fn is_firing(&self) -> bool {
+        self.warning.finished()
+    }
+
+    fn angle(&self) -> f32 {
+        self.start_angle + self.arc * self.sweep.fraction()
+    }
+
+    /// Whether the firing beam touches `aabb`. The beam is a segment, so the box is grown by
+    /// half the beam width to account for its thickness.
+    pub fn hits(&self, aabb: &Aabb2d) -> bool {
+        if !self.is_firing() {
+            return false;
+        }
+        let direction = Dir2::new_unchecked(Vec2::from_angle(self.angle()));
+        RayCast2d::new(self.origin, direction, BEAM_LENGTH)
+            .aabb_intersection_at(&aabb.grow(Vec2::splat(0.5 * self.width)))
+            .is_some()
+    }
+}
+
+fn beam_transform(origin: Vec2, angle: f32) -> Transform {
+    let center = origin + 0.5 * BEAM_LENGTH * Vec2::from_angle(angle);
+    Transform::from_translation(center.extend(-0.5)).with_rotation(Quat::from_rotation_z(angle))
+}
+
+fn park_laser_sweepers(
+    mut commands: Commands,
+    mut sweepers: Query<(
+        Entity,
+        &Transform,
+        &Hitbox,
+        &Enemy,
+        &mut Velocity,
+        &mut LaserSweeper,
+    )>,
+) {
+    for (entity, transform, hitbox, enemy, mut velocity, mut sweeper) in sweepers.iter_mut() {
+        let inside = transform
+            .translation
+            .abs()
+            .cmple(arena_boundary(hitbox.0.x))
+            .all();
+        if sweeper.parked || !inside {
+            continue;
+        }
+        sweeper.parked = true;
+        velocity.value = Vec3::ZERO;
+
+        let sweeping = sweeper.sweeping;
+        let origin = transform.translation.xy();
+        let arc = sweeping.arc.to_radians();
+        let start_angle = (-origin).to_angle() - 0.5 * arc;
+        commands.spawn((
+            LaserBeam {
+                source: entity,
+                enemy: *enemy,
+                origin,
+                start_angle,
+                arc,
+                width: sweeping.width,
+                warning: Timer::from_seconds(sweeping.warning, TimerMode::Once),
+                sweep: Timer::from_seconds(sweeping.duration, TimerMode::Once),
+            },
+            Sprite::from_color(
+                sweeper.color.with_alpha(WARNING_LINE_ALPHA),
+                Vec2::new(BEAM_LENGTH, WARNING_LINE_WIDTH),
+            ),
+            beam_transform(origin, start_angle),
+        ));
+    }
+}
+
+/// Fires beams once their warning is over and sweeps them. A beam that is done sends its
+/// [LaserSweeper] back out of the arena.
+fn sweep_laser_beams(
+    mut commands: Commands,
+    mut beams: Query<(Entity, &mut LaserBeam, &mut Transform, &mut Sprite)>,
+    mut sweepers: Query<(&LaserSweeper, &mut Velocity, Option<&MovementScale>)>,
+    time: Res<Time>,
+) {
+    for (entity, mut beam, mut transform, mut sprite) in beams.iter_mut() {
+        let Ok((sweeper, mut velocity, scale)) = sweepers.get_mut(beam.source) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let delta = time.delta().mul_f32(scale.map_or(1.0, |scale| scale.0));
+        if !beam.warning.tick(delta).finished() {
+            continue;
+        }
+        if beam.warning.just_finished() {
+            sprite.color = sweeper.color;
+            sprite.custom_size = Some(Vec2::new(BEAM_LENGTH, beam.width));
+        }
+        if beam.sweep.tick(delta).finished() {
+            commands.entity(entity).despawn();
+            velocity.value = beam.origin.normalize_or(Vec2::Y).extend(0.0) * sweeper.speed;
+            continue;
+        }
+        *transform = beam_transform(beam.origin, beam.angle());
+    }
+}
+
+fn despawn_all_beams(mut commands: Commands, beams: Query<Entity, With<LaserBeam>>) {
+    beams
+        .iter()
+        .for_each(|entity| commands.entity(entity).despawn());
+}
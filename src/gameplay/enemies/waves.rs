@@ -0,0 +1,197 @@
+use super::{ENEMY_SPAWN_RADIUS, Enemy, EnemyDefinitions, EnemySet, spawn_single_enemy};
+use crate::assets::{DataAssets, RonAssetPlugin};
+use crate::world::State;
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+use serde::Deserialize;
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<WaveScript>::new(&["wave.ron"]))
+            .init_resource::<WaveScriptPath>()
+            .init_resource::<WaveDirector>()
+            .add_systems(
+                PreUpdate,
+                load_wave_script.run_if(resource_changed::<WaveScriptPath>),
+            )
+            .add_systems(FixedUpdate, direct_waves.in_set(EnemySet::Common))
+            .add_systems(OnEnter(State::Starting), reset_wave_director);
+    }
+}
+
+#[derive(Resource, Clone, Default, Debug)]
+/// The wave script played during a run, relative to `assets/`. `None` plays no script.
+pub struct WaveScriptPath(pub Option<String>);
+
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+/// Authored spawns that happen at set times during a run.
+pub struct WaveScript {
+    /// Whether the random spawner keeps running while the script plays.
+    #[serde(default = "default_random_spawning")]
+    pub random_spawning: bool,
+    pub events: Vec<WaveEvent>,
+}
+
+fn default_random_spawning() -> bool {
+    true
+}
+
+#[derive(Deserialize, Clone, Debug)]
+/// Spawns `count` enemies, starting `at` seconds into the run.
+pub struct WaveEvent {
+    pub at: f32,
+    pub enemy: Enemy,
+    pub placement: Placement,
+    #[serde(default = "default_count")]
+    pub count: u32,
+    #[serde(default)]
+    pub spacing: Spacing,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum Placement {
+    /// On the spawn circle around the arena at `angle` degrees, heading for the center.
+    /// An angle of 0 is straight above the arena, angles go counterclockwise.
+    Edge { angle: f32 },
+    /// At `position`, heading in `direction`.
+    Point { position: Vec2, direction: Vec2 },
+}
+
+#[derive(Deserialize, Clone, Copy, Default, Debug)]
+/// How the enemies of one [WaveEvent] are spread out.
+pub struct Spacing {
+    /// Degrees between consecutive enemies, rotating both their position and direction
+    /// around the center of the arena.
+    #[serde(default)]
+    pub angle: f32,
+    /// Seconds between consecutive enemies.
+    #[serde(default)]
+    pub time: f32,
+}
+
+/// A single enemy of a [WaveEvent], ready to be spawned.
+struct ScheduledSpawn {
+    at: f32,
+    enemy: Enemy,
+    position: Vec3,
+    direction: Vec3,
+}
+
+impl WaveScript {
+    /// Every enemy the script spawns, sorted by time.
+    fn schedule(&self) -> Vec<ScheduledSpawn> {
+        let mut schedule: Vec<ScheduledSpawn> = self
+            .events
+            .iter()
+            .flat_map(|event| {
+                (0..event.count).map(move |i| {
+                    let (position, direction) = match event.placement {
+                        Placement::Edge { angle } => {
+                            let position = Vec2::from_angle(angle.to_radians()).rotate(Vec2::Y)
+                                * ENEMY_SPAWN_RADIUS;
+                            (position, -position.normalize())
+                        }
+                        Placement::Point {
+                            position,
+                            direction,
+                        } => (position, direction.normalize_or(Vec2::NEG_Y)),
+                    };
+                    let rotation = Vec2::from_angle((i as f32 * event.spacing.angle).to_radians());
+                    ScheduledSpawn {
+                        at: event.at + i as f32 * event.spacing.time,
+                        enemy: event.enemy,
+                        position: rotation.rotate(position).extend(0.0),
+                        direction: rotation.rotate(direction).extend(0.0),
+                    }
+                })
+            })
+            .collect();
+        schedule.sort_by(|a, b| a.at.total_cmp(&b.at));
+        schedule
+    }
+}
+
+#[derive(Resource, Default)]
+/// Plays the [WaveScript] back during a run.
+pub struct WaveDirector {
+    script: Option<Handle<WaveScript>>,
+    schedule: Option<Vec<ScheduledSpawn>>,
+    random_spawning: bool,
+    next: usize,
+    clock: Stopwatch,
+}
+
+impl WaveDirector {
+    /// Whether the script that is playing leaves room for random spawning.
+    pub fn allows_random_spawning(&self) -> bool {
+        self.schedule.is_none() || self.random_spawning
+    }
+
+    fn build_schedule(&mut self, scripts: &Assets<WaveScript>) {
+        if self.schedule.is_some() {
+            return;
+        }
+        if let Some(script) = self.script.as_ref().and_then(|handle| scripts.get(handle)) {
+            self.random_spawning = script.random_spawning;
+            self.schedule = Some(script.schedule());
+        }
+    }
+}
+
+fn load_wave_script(
+    path: Res<WaveScriptPath>,
+    asset_server: Res<AssetServer>,
+    mut data_assets: ResMut<DataAssets>,
+    mut director: ResMut<WaveDirector>,
+) {
+    director.script = path.0.as_ref().map(|path| {
+        let handle = asset_server.load(path);
+        data_assets.track(handle.clone());
+        handle
+    });
+    director.schedule = None;
+}
+
+fn reset_wave_director(mut director: ResMut<WaveDirector>, scripts: Res<Assets<WaveScript>>) {
+    director.schedule = None;
+    director.next = 0;
+    director.clock.reset();
+    director.build_schedule(&scripts);
+}
+
+fn direct_waves(
+    mut commands: Commands,
+    mut director: ResMut<WaveDirector>,
+    scripts: Res<Assets<WaveScript>>,
+    definitions: Res<EnemyDefinitions>,
+    time: Res<Time>,
+) {
+    let director = &mut *director;
+    director.clock.tick(time.delta());
+    // The script may still have been loading when the run started.
+    director.build_schedule(&scripts);
+
+    let Some(schedule) = &director.schedule else {
+        return;
+    };
+    let elapsed = director.clock.elapsed_secs();
+    while let Some(spawn) = schedule.get(director.next) {
+        if spawn.at > elapsed {
+            break;
+        }
+        spawn_single_enemy(
+            spawn.enemy,
+            spawn.position,
+            spawn.direction,
+            &definitions,
+            &mut commands,
+        );
+        director.next += 1;
+    }
+}
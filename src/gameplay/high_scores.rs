@@ -0,0 +1,186 @@
+use crate::gameplay::enemies::Enemy;
+use crate::gameplay::mode::GameMode;
+use crate::gameplay::player::CauseOfDeath;
+use crate::gameplay::replay::ReplayPlayback;
+use crate::gameplay::rng::RunSeed;
+use crate::gameplay::score::Score;
+use crate::world::State;
+use bevy::prelude::*;
+use bevy::utils::SystemTime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+
+/// How many runs the table of each [GameMode] keeps.
+pub const HIGH_SCORE_TABLE_SIZE: usize = 10;
+
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HighScores>()
+            .insert_resource(SaveHighScores(true))
+            .add_systems(OnEnter(State::Starting), forget_latest_high_score)
+            .add_systems(
+                OnEnter(State::GameOver),
+                record_high_score.run_if(not(resource_exists::<ReplayPlayback>)),
+            );
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(
+            Startup,
+            load_high_scores.run_if(|save: Res<SaveHighScores>| save.0),
+        )
+        .add_systems(
+            OnEnter(State::GameOver),
+            save_high_scores
+                .after(record_high_score)
+                .run_if(|save: Res<SaveHighScores>| save.0)
+                .run_if(not(resource_exists::<ReplayPlayback>)),
+        );
+    }
+}
+
+#[derive(Resource)]
+/// Whether the high score table is read from and written to disk.
+pub struct SaveHighScores(pub bool);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HighScoreEntry {
+    pub score: u32,
+    /// Seconds since the unix epoch.
+    pub date: u64,
+    pub seed: u64,
+    pub mode: GameMode,
+    pub cause: Option<Enemy>,
+}
+
+impl HighScoreEntry {
+    /// The date the run ended on as `YYYY-MM-DD`, in UTC.
+    pub fn date_string(&self) -> String {
+        // Days to civil date, from http://howardhinnant.github.io/date_algorithms.html
+        let days = (self.date / 86400) as i64 + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+/// The best runs of each [GameMode], highest score first.
+pub struct HighScores {
+    tables: BTreeMap<GameMode, Vec<HighScoreEntry>>,
+    /// Mode and position of the entry the last run earned, if it made the table.
+    #[serde(skip)]
+    latest: Option<(GameMode, usize)>,
+}
+
+impl HighScores {
+    pub fn entries(&self, mode: GameMode) -> &[HighScoreEntry] {
+        self.tables.get(&mode).map_or(&[], Vec::as_slice)
+    }
+
+    /// Position of the entry the last run earned in the table of `mode`.
+    pub fn latest(&self, mode: GameMode) -> Option<usize> {
+        self.latest
+            .filter(|(latest_mode, _)| *latest_mode == mode)
+            .map(|(_, position)| position)
+    }
+
+    /// Adds the entry to the table of its mode if it makes it and returns its position.
+    /// Ties go below the runs that got there first.
+    pub fn record(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        let mode = entry.mode;
+        let entries = self.tables.entry(mode).or_default();
+        let position = entries
+            .iter()
+            .position(|existing| existing.score < entry.score)
+            .unwrap_or(entries.len());
+        let position = (position < HIGH_SCORE_TABLE_SIZE).then(|| {
+            entries.insert(position, entry);
+            entries.truncate(HIGH_SCORE_TABLE_SIZE);
+            position
+        });
+        self.latest = position.map(|position| (mode, position));
+        position
+    }
+
+    pub fn load(path: &Path) -> Result<HighScores, Box<dyn Error>> {
+        Ok(ron::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(
+            path,
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?,
+        )?;
+        Ok(())
+    }
+}
+
+fn forget_latest_high_score(mut high_scores: ResMut<HighScores>) {
+    high_scores.latest = None;
+}
+
+pub fn record_high_score(
+    mut high_scores: ResMut<HighScores>,
+    score: Res<Score>,
+    seed: Res<RunSeed>,
+    mode: Res<GameMode>,
+    cause_of_death: Res<CauseOfDeath>,
+) {
+    let date = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    high_scores.record(HighScoreEntry {
+        score: score.0,
+        date,
+        seed: seed.0,
+        mode: *mode,
+        cause: cause_of_death.0,
+    });
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn high_scores_path() -> std::path::PathBuf {
+    crate::storage::data_dir().join("high_scores.ron")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_high_scores(mut high_scores: ResMut<HighScores>) {
+    let path = high_scores_path();
+    if !path.exists() {
+        return;
+    }
+    match HighScores::load(&path) {
+        Ok(loaded) => *high_scores = loaded,
+        Err(error) => error!(
+            "Could not load high scores from {}: {error}",
+            path.display()
+        ),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_high_scores(high_scores: Res<HighScores>) {
+    let path = high_scores_path();
+    if let Err(error) = high_scores.save(&path) {
+        error!("Could not save high scores to {}: {error}", path.display());
+    }
+}
@@ -0,0 +1,154 @@
+use crate::gameplay::GameplaySet;
+use crate::gameplay::player::Player;
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+mod bindings;
+mod pointer;
+pub use bindings::{Action, Actions, Binding, InputBindings};
+pub use pointer::SteeringMode;
+
+/// The [StickDeadzone] until the player picks another one in the settings.
+pub const DEFAULT_STICK_DEADZONE: f32 = 0.15;
+
+pub struct PlayerInputPlugin;
+
+impl Plugin for PlayerInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((bindings::BindingsPlugin, pointer::PointerPlugin))
+            .configure_sets(
+                FixedUpdate,
+                (PlayerInputSet::Read, PlayerInputSet::Override)
+                    .chain()
+                    .in_set(GameplaySet::Input),
+            )
+            .insert_resource(StickDeadzone(DEFAULT_STICK_DEADZONE))
+            .add_systems(
+                FixedUpdate,
+                (
+                    read_action_input.run_if(resource_exists::<ButtonInput<KeyCode>>),
+                    read_gamepad_stick,
+                    pointer::read_touch_steering.run_if(
+                        resource_exists::<Touches>.and(resource_equals(SteeringMode::Touch)),
+                    ),
+                )
+                    .chain()
+                    .in_set(PlayerInputSet::Read),
+            )
+            .add_systems(
+                Update,
+                log_gamepad_connections.run_if(resource_exists::<Events<GamepadConnectionEvent>>),
+            );
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug)]
+/// Stick deflection, between 0 and 1, below which the stick counts as centered.
+/// Deflection past it is rescaled so movement still starts from zero.
+pub struct StickDeadzone(pub f32);
+
+impl StickDeadzone {
+    pub fn apply(&self, stick: Vec2) -> Vec2 {
+        let deflection = stick.length();
+        if deflection <= self.0 {
+            return Vec2::ZERO;
+        }
+        let scaled = ((deflection - self.0) / (1.0 - self.0)).min(1.0);
+        stick / deflection * scaled
+    }
+}
+
+/// Systems that fill in [PlayerInput] for the current tick.
+/// Anything that consumes [PlayerInput] should run after [PlayerInputSet::Override].
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PlayerInputSet {
+    /// Reads input devices.
+    Read,
+    /// Replaces what was read, for example when playing back a replay.
+    Override,
+}
+
+#[derive(Component, Default, Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+/// What the player wants to do this tick, independent of where the input came from.
+pub struct PlayerInput {
+    /// Direction to accelerate in, with a length of at most 1 for analog input.
+    pub movement: Vec2,
+    /// Whether the dash button is held, a dash starts when it goes down.
+    pub dash: bool,
+    /// Whether the shockwave button is held, the shockwave fires when it goes down.
+    pub shockwave: bool,
+    /// Whether the player focuses: they move slower with a smaller hitbox.
+    pub focus: bool,
+}
+
+fn read_action_input(actions: Actions, mut players: Query<(&Player, &mut PlayerInput)>) {
+    for (player, mut player_input) in players.iter_mut() {
+        let pressed = |action| actions.pressed(player.index, action);
+        let mut input_vector = Vec2::ZERO;
+        if pressed(Action::MoveLeft) {
+            input_vector.x -= 1.0;
+        }
+        if pressed(Action::MoveRight) {
+            input_vector.x += 1.0;
+        }
+        if pressed(Action::MoveUp) {
+            input_vector.y += 1.0;
+        }
+        if pressed(Action::MoveDown) {
+            input_vector.y -= 1.0;
+        }
+
+        player_input.movement = input_vector.normalize_or_zero();
+        player_input.dash = pressed(Action::Dash);
+        player_input.shockwave = pressed(Action::Shockwave);
+        player_input.focus = pressed(Action::Focus);
+    }
+}
+
+/// Adds the left stick of the gamepads of each player on top of the bound [Action]s.
+fn read_gamepad_stick(
+    actions: Actions,
+    deadzone: Res<StickDeadzone>,
+    mut players: Query<(&Player, &mut PlayerInput)>,
+) {
+    for (player, mut player_input) in players.iter_mut() {
+        for gamepad in actions.gamepads(player.index) {
+            let stick = deadzone.apply(gamepad.left_stick());
+            player_input.movement = (player_input.movement + stick).clamp_length_max(1.0);
+        }
+    }
+}
+
+fn log_gamepad_connections(mut connection_events: EventReader<GamepadConnectionEvent>) {
+    for event in connection_events.read() {
+        match &event.connection {
+            GamepadConnection::Connected { name, .. } => info!("Gamepad {name} connected"),
+            GamepadConnection::Disconnected => info!("Gamepad disconnected"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadzone_centers_small_deflections() {
+        let deadzone = StickDeadzone(0.2);
+        assert_eq!(deadzone.apply(Vec2::new(0.1, 0.1)), Vec2::ZERO);
+        assert_eq!(deadzone.apply(Vec2::new(0.0, 0.2)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn deadzone_rescales_the_rest_of_the_range() {
+        let deadzone = StickDeadzone(0.2);
+        let just_past = deadzone.apply(Vec2::new(0.0, 0.21));
+        assert!(just_past.y > 0.0 && just_past.y < 0.02);
+        assert!((deadzone.apply(Vec2::new(0.6, 0.0)) - Vec2::new(0.5, 0.0)).length() < 1e-6);
+        assert!((deadzone.apply(Vec2::new(0.0, -1.0)) - Vec2::NEG_Y).length() < 1e-6);
+        // Corners of a square stick gate go past full deflection but not past 1.
+        assert!((deadzone.apply(Vec2::ONE).length() - 1.0).abs() < 1e-6);
+    }
+}
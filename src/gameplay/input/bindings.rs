@@ -0,0 +1,309 @@
+use crate::gameplay::player::{MAX_PLAYERS, PlayerCount};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::Path;
+
+pub struct BindingsPlugin;
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputBindings>();
+
+        // Only an app that reads input devices has bindings worth loading.
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(
+            Startup,
+            load_bindings.run_if(resource_exists::<ButtonInput<KeyCode>>),
+        );
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+/// Something the player can do, independent of the key or button it is bound to.
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Dash,
+    Shockwave,
+    Focus,
+    Pause,
+    Restart,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Dash,
+        Action::Shockwave,
+        Action::Focus,
+        Action::Pause,
+        Action::Restart,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Dash => "Dash",
+            Action::Shockwave => "Shockwave",
+            Action::Focus => "Focus",
+            Action::Pause => "Pause",
+            Action::Restart => "Restart",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    GamepadButton(GamepadButton),
+}
+
+impl Binding {
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("{key:?}"),
+            Binding::GamepadButton(button) => format!("Pad {button:?}"),
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+/// The keys and buttons bound to each [Action] of every player, any of them triggers it.
+/// Gamepad buttons are pressed on the gamepads of the player, see [Actions::gamepads].
+pub struct InputBindings {
+    players: Vec<BTreeMap<Action, Vec<Binding>>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use Binding::{GamepadButton as Pad, Key};
+        let gamepad_bindings = |action| match action {
+            Action::MoveUp => vec![Pad(GamepadButton::DPadUp)],
+            Action::MoveDown => vec![Pad(GamepadButton::DPadDown)],
+            Action::MoveLeft => vec![Pad(GamepadButton::DPadLeft)],
+            Action::MoveRight => vec![Pad(GamepadButton::DPadRight)],
+            Action::Dash => vec![Pad(GamepadButton::South), Pad(GamepadButton::RightTrigger2)],
+            Action::Shockwave => vec![Pad(GamepadButton::West), Pad(GamepadButton::LeftTrigger)],
+            Action::Focus => vec![
+                Pad(GamepadButton::RightTrigger),
+                Pad(GamepadButton::LeftTrigger2),
+            ],
+            Action::Pause | Action::Restart => vec![Pad(GamepadButton::Start)],
+        };
+        let key_bindings = |player, action| match (player, action) {
+            (0, Action::MoveUp) => vec![Key(KeyCode::ArrowUp), Key(KeyCode::KeyW)],
+            (0, Action::MoveDown) => vec![Key(KeyCode::ArrowDown), Key(KeyCode::KeyS)],
+            (0, Action::MoveLeft) => vec![Key(KeyCode::ArrowLeft), Key(KeyCode::KeyA)],
+            (0, Action::MoveRight) => vec![Key(KeyCode::ArrowRight), Key(KeyCode::KeyD)],
+            (0, Action::Dash) => vec![Key(KeyCode::Space)],
+            (0, Action::Shockwave) => vec![Key(KeyCode::KeyE)],
+            (0, Action::Focus) => vec![Key(KeyCode::ShiftLeft)],
+            (0, Action::Pause) => vec![Key(KeyCode::Escape), Key(KeyCode::KeyP)],
+            (0, Action::Restart) => vec![Key(KeyCode::KeyR)],
+            // A second player shares the keyboard on the other side.
+            (1, Action::MoveUp) => vec![Key(KeyCode::KeyI)],
+            (1, Action::MoveDown) => vec![Key(KeyCode::KeyK)],
+            (1, Action::MoveLeft) => vec![Key(KeyCode::KeyJ)],
+            (1, Action::MoveRight) => vec![Key(KeyCode::KeyL)],
+            (1, Action::Dash) => vec![Key(KeyCode::KeyU)],
+            (1, Action::Shockwave) => vec![Key(KeyCode::KeyO)],
+            (1, Action::Focus) => vec![Key(KeyCode::ShiftRight)],
+            _ => Vec::new(),
+        };
+        let players = (0..MAX_PLAYERS)
+            .map(|player| {
+                Action::ALL
+                    .iter()
+                    .map(|action| {
+                        let mut bindings = key_bindings(player, *action);
+                        bindings.extend(gamepad_bindings(*action));
+                        (*action, bindings)
+                    })
+                    .collect()
+            })
+            .collect();
+        InputBindings { players }
+    }
+}
+
+impl InputBindings {
+    pub fn get(&self, player: usize, action: Action) -> &[Binding] {
+        self.players
+            .get(player)
+            .and_then(|bindings| bindings.get(&action))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Binds `binding` to `action` of `player`, or unbinds it when it already was.
+    /// A key or button triggers a single action, so it is taken away from any other action.
+    /// Keys are shared by all players, gamepad buttons only by the actions of one player.
+    pub fn toggle(&mut self, player: usize, action: Action, binding: Binding) {
+        if player >= self.players.len() {
+            return;
+        }
+        let was_bound = self.get(player, action).contains(&binding);
+        for (index, bindings) in self.players.iter_mut().enumerate() {
+            if index != player && matches!(binding, Binding::GamepadButton(_)) {
+                continue;
+            }
+            for bound in bindings.values_mut() {
+                bound.retain(|bound| *bound != binding);
+            }
+        }
+        if !was_bound {
+            self.players[player]
+                .entry(action)
+                .or_default()
+                .push(binding);
+        }
+    }
+
+    /// Reads bindings from `path`, actions missing from the file keep their default bindings.
+    /// Those defaults lose the keys and buttons the file binds elsewhere, following the same
+    /// rules as [InputBindings::toggle].
+    pub fn load(path: &Path) -> Result<InputBindings, Box<dyn Error>> {
+        let loaded: InputBindings = ron::from_str(&std::fs::read_to_string(path)?)?;
+        let loaded_keys: Vec<Binding> = loaded
+            .players
+            .iter()
+            .flat_map(|bindings| bindings.values().flatten())
+            .filter(|binding| matches!(binding, Binding::Key(_)))
+            .copied()
+            .collect();
+        let mut bindings = InputBindings::default();
+        for (defaults, loaded) in bindings.players.iter_mut().zip(loaded.players) {
+            for (action, bound) in defaults.iter_mut() {
+                if loaded.contains_key(action) {
+                    continue;
+                }
+                bound.retain(|binding| {
+                    !loaded_keys.contains(binding)
+                        && !loaded.values().flatten().any(|b| b == binding)
+                });
+            }
+            defaults.extend(loaded);
+        }
+        Ok(bindings)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(
+            path,
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?,
+        )?;
+        Ok(())
+    }
+
+    /// Saves the bindings so they survive a restart. Bindings only last the session in the browser.
+    pub fn persist(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = bindings_path();
+            if let Err(error) = self.save(&path) {
+                error!("Could not save bindings to {}: {error}", path.display());
+            }
+        }
+    }
+}
+
+#[derive(SystemParam)]
+/// Reads [Action]s through the [InputBindings], for systems that should not care about devices.
+pub struct Actions<'w, 's> {
+    bindings: Res<'w, InputBindings>,
+    player_count: Res<'w, PlayerCount>,
+    keyboard_input: Option<Res<'w, ButtonInput<KeyCode>>>,
+    gamepads: Query<'w, 's, (Entity, &'static Gamepad)>,
+}
+
+impl Actions<'_, '_> {
+    /// The gamepads that steer `player`. Gamepads are dealt out to the players in the order
+    /// they connected, so a single player can pick up any of them.
+    pub fn gamepads(&self, player: usize) -> impl Iterator<Item = &Gamepad> {
+        let mut gamepads: Vec<_> = self.gamepads.iter().collect();
+        gamepads.sort_by_key(|(entity, _)| *entity);
+        let count = self.player_count.0.max(1);
+        gamepads
+            .into_iter()
+            .enumerate()
+            .filter(move |(position, _)| position % count == player)
+            .map(|(_, (_, gamepad))| gamepad)
+    }
+
+    /// Whether any binding of `action` of `player` is held.
+    pub fn pressed(&self, player: usize, action: Action) -> bool {
+        self.any_binding(
+            player,
+            action,
+            |keyboard_input, key| keyboard_input.pressed(key),
+            |gamepad, button| gamepad.pressed(button),
+        )
+    }
+
+    /// Whether any binding of `action` of `player` went down this frame.
+    pub fn just_pressed(&self, player: usize, action: Action) -> bool {
+        self.any_binding(
+            player,
+            action,
+            |keyboard_input, key| keyboard_input.just_pressed(key),
+            |gamepad, button| gamepad.just_pressed(button),
+        )
+    }
+
+    /// Whether any player's binding of `action` went down this frame,
+    /// for actions like [Action::Pause] that are not tied to a player.
+    pub fn any_just_pressed(&self, action: Action) -> bool {
+        (0..self.player_count.0.max(1)).any(|player| self.just_pressed(player, action))
+    }
+
+    fn any_binding(
+        &self,
+        player: usize,
+        action: Action,
+        key_down: impl Fn(&ButtonInput<KeyCode>, KeyCode) -> bool,
+        button_down: impl Fn(&Gamepad, GamepadButton) -> bool,
+    ) -> bool {
+        self.bindings
+            .get(player, action)
+            .iter()
+            .any(|binding| match binding {
+                Binding::Key(key) => self
+                    .keyboard_input
+                    .as_ref()
+                    .is_some_and(|keyboard_input| key_down(keyboard_input, *key)),
+                Binding::GamepadButton(button) => self
+                    .gamepads(player)
+                    .any(|gamepad| button_down(gamepad, *button)),
+            })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn bindings_path() -> std::path::PathBuf {
+    crate::storage::data_dir().join("bindings.ron")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_bindings(mut bindings: ResMut<InputBindings>) {
+    let path = bindings_path();
+    if !path.exists() {
+        return;
+    }
+    match InputBindings::load(&path) {
+        Ok(loaded) => *bindings = loaded,
+        Err(error) => error!("Could not load bindings from {}: {error}", path.display()),
+    }
+}
@@ -0,0 +1,90 @@
+use super::PlayerInput;
+use crate::gameplay::player::Player;
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+
+/// Distance from the touch, in world units, within which the player starts to slow down.
+const SLOWDOWN_DISTANCE: f32 = 100.0;
+/// Distance from the touch at which the player counts as arrived, so it does not jitter around it.
+const ARRIVED_DISTANCE: f32 = 4.0;
+
+/// Steers the player toward a held touch, for phones and tablets that have no keyboard.
+pub struct PointerPlugin;
+
+impl Plugin for PointerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SteeringMode>().add_systems(
+            PreUpdate,
+            detect_steering_mode
+                .run_if(resource_exists::<Touches>)
+                .after(bevy::input::InputSystem),
+        );
+    }
+}
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+/// How the player is steered, switched to whichever device was used last.
+pub enum SteeringMode {
+    #[default]
+    Buttons,
+    /// The player follows the first finger on the screen, a second finger dashes.
+    Touch,
+}
+
+fn detect_steering_mode(
+    touches: Res<Touches>,
+    keyboard_input: Option<Res<ButtonInput<KeyCode>>>,
+    gamepads: Query<&Gamepad>,
+    mut mode: ResMut<SteeringMode>,
+) {
+    let buttons_used = keyboard_input
+        .is_some_and(|keyboard_input| keyboard_input.get_just_pressed().next().is_some())
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some());
+
+    let detected = if touches.any_just_pressed() {
+        SteeringMode::Touch
+    } else if buttons_used {
+        SteeringMode::Buttons
+    } else {
+        return;
+    };
+    if *mode != detected {
+        info!("Steering with {detected:?}");
+        *mode = detected;
+    }
+}
+
+/// Adds steering toward the touched world position on top of the other input of the first player.
+/// The player slows down as it nears the touch, so it comes to rest under the finger.
+pub(super) fn read_touch_steering(
+    touches: Res<Touches>,
+    camera: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    mut players: Query<(&Player, &mut PlayerInput, &Transform)>,
+) {
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+    let Some((_, mut player_input, player_transform)) =
+        players.iter_mut().find(|(player, _, _)| player.index == 0)
+    else {
+        return;
+    };
+
+    let mut held = touches.iter();
+    let Some(steering_touch) = held.next() else {
+        return;
+    };
+    let Ok(target) = camera.viewport_to_world_2d(camera_transform, steering_touch.position())
+    else {
+        return;
+    };
+
+    let offset = target - player_transform.translation.truncate();
+    if offset.length() > ARRIVED_DISTANCE {
+        let steering = (offset / SLOWDOWN_DISTANCE).clamp_length_max(1.0);
+        player_input.movement = (player_input.movement + steering).clamp_length_max(1.0);
+    }
+    player_input.dash |= held.next().is_some();
+}
@@ -0,0 +1,80 @@
+use crate::gameplay::enemies::WaveScriptPath;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct GameModePlugin;
+
+impl Plugin for GameModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameMode>();
+    }
+}
+
+#[derive(
+    Resource,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+/// The rules the next run is played with.
+/// Switch modes with [GameMode::select] so the matching wave script is loaded as well.
+pub enum GameMode {
+    #[default]
+    /// Random spawning only.
+    Classic,
+    /// A fixed, fully authored run.
+    Gauntlet,
+    /// Classic, but the player survives a couple of hits.
+    Casual,
+    /// Classic with set pieces on top of the random spawning.
+    Onslaught,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Classic,
+        GameMode::Gauntlet,
+        GameMode::Casual,
+        GameMode::Onslaught,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "Classic",
+            GameMode::Gauntlet => "Gauntlet",
+            GameMode::Casual => "Casual",
+            GameMode::Onslaught => "Onslaught",
+        }
+    }
+
+    /// The wave script the mode plays, relative to `assets/`.
+    pub fn wave_script_path(&self) -> Option<&'static str> {
+        match self {
+            GameMode::Classic | GameMode::Casual => None,
+            GameMode::Gauntlet => Some("waves/gauntlet.wave.ron"),
+            GameMode::Onslaught => Some("waves/onslaught.wave.ron"),
+        }
+    }
+
+    /// How many hits the player can take before the run ends.
+    pub fn player_hits(&self) -> u32 {
+        match self {
+            GameMode::Classic | GameMode::Gauntlet | GameMode::Onslaught => 1,
+            GameMode::Casual => 3,
+        }
+    }
+
+    /// Makes this the mode of the next run.
+    pub fn select(self, commands: &mut Commands) {
+        commands.insert_resource(self);
+        commands.insert_resource(WaveScriptPath(self.wave_script_path().map(String::from)));
+    }
+}
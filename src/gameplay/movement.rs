@@ -1,4 +1,3 @@
-use crate::world::State;
 use bevy::prelude::*;
 
 pub struct MovementPlugin;
@@ -7,13 +6,18 @@ impl Plugin for MovementPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             FixedUpdate,
-            (apply_acceleration, apply_velocity)
+            (apply_acceleration, apply_orbit, apply_velocity)
                 .chain()
-                .run_if(in_state(State::Playing)),
+                .in_set(MovementSet),
         );
     }
 }
 
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// Moves everything with a [Velocity], see [crate::gameplay::GameplaySet] for what goes before
+/// and after it.
+pub struct MovementSet;
+
 #[derive(Component, Default)]
 pub struct Velocity {
     pub value: Vec3,
@@ -24,25 +28,81 @@ impl Velocity {
     pub fn new(value: Vec3, max: f32) -> Self {
         Velocity { value, max }
     }
+
+    /// Changes the velocity that [Acceleration] aims for at full length.
+    pub fn set_max(&mut self, max: f32) {
+        self.max = max;
+    }
 }
 
 #[derive(Component, Default)]
 pub struct Acceleration {
+    /// Where to accelerate to, a length below 1 aims for less than the maximum velocity.
     pub direction: Vec3,
     pub amount: f32,
 }
 
-fn apply_velocity(mut query: Query<(&Velocity, &mut Transform)>, time: Res<Time>) {
-    for result in query.iter_mut() {
-        let (velocity, mut transform) = result;
-        transform.translation += velocity.value * time.delta_secs();
+#[derive(Component)]
+/// Circles `center` while the radius shrinks, by steering the [Velocity] no faster than its
+/// maximum. Once the radius is used up the orbit ends and the entity keeps its last velocity.
+/// A [MovementScale] slows the whole orbit down, the shrinking as well.
+pub struct Orbit {
+    /// What to circle around, move it to have the orbit follow a target.
+    pub center: Vec3,
+    pub radius: f32,
+    /// Radians per second, counterclockwise when positive.
+    pub angular_speed: f32,
+    /// How much the radius shrinks per second.
+    pub decay: f32,
+}
+
+#[derive(Component)]
+/// Factor on how far the [Velocity] moves an entity, for slowing it down without changing its course.
+pub struct MovementScale(pub f32);
+
+fn apply_velocity(
+    mut query: Query<(&Velocity, &mut Transform, Option<&MovementScale>)>,
+    time: Res<Time>,
+) {
+    for (velocity, mut transform, scale) in query.iter_mut() {
+        let scale = scale.map_or(1.0, |scale| scale.0);
+        transform.translation += velocity.value * scale * time.delta_secs();
+    }
+}
+
+fn apply_orbit(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &mut Orbit,
+        &Transform,
+        &mut Velocity,
+        Option<&MovementScale>,
+    )>,
+    time: Res<Time>,
+) {
+    for (entity, mut orbit, transform, mut velocity, scale) in query.iter_mut() {
+        // `apply_velocity` scales the velocity again, which lands the entity on the orbit.
+        let delta = time.delta_secs() * scale.map_or(1.0, |scale| scale.0);
+        if delta <= 0.0 {
+            continue;
+        }
+        orbit.radius -= orbit.decay * delta;
+        if orbit.radius <= 0.0 {
+            commands.entity(entity).remove::<Orbit>();
+            continue;
+        }
+        let offset = (transform.translation - orbit.center).xy();
+        let angle = offset.to_angle() + orbit.angular_speed * delta;
+        let target = orbit.center + (orbit.radius * Vec2::from_angle(angle)).extend(0.0);
+        velocity.value = ((target - transform.translation) / delta).clamp_length_max(velocity.max);
     }
 }
 
 fn apply_acceleration(mut query: Query<(&Acceleration, &mut Velocity)>, time: Res<Time>) {
     for (acceleration, mut velocity) in query.iter_mut() {
         velocity.value = velocity.value.lerp(
-            acceleration.direction.normalize_or_zero() * velocity.max,
+            acceleration.direction.clamp_length_max(1.0) * velocity.max,
             1.0 - f32::exp(-acceleration.amount * time.delta_secs()),
         );
     }
@@ -1,13 +1,35 @@
-use crate::gameplay::enemies::Enemy;
+use crate::gameplay::GameplaySet;
+use crate::gameplay::enemies::{Boss, Enemy, Hitbox, LaserBeam, Unarmed};
+use crate::gameplay::input::PlayerInput;
+use crate::gameplay::mode::GameMode;
 use crate::gameplay::movement::{Acceleration, Velocity};
-use crate::world::{State, WORLD_BOUNDARY_VECTOR};
+use crate::gameplay::power_ups::Shield;
+use crate::gameplay::ships::{ShipSelection, ShipStats};
+use crate::gameplay::shockwave::Shockwave;
+use crate::world::State;
+use bevy::ecs::system::SystemParam;
 use bevy::math::bounding::{Aabb2d, IntersectsVolume};
 use bevy::prelude::*;
 
-pub const PLAYER_MAX_SPEED: f32 = 300.0;
-pub const PLAYER_ACCELERATION: f32 = 50.0;
-pub const PLAYER_DRAG: f32 = 50.0;
-pub const PLAYER_SIZE: f32 = 20.0;
+/// How long the player can not be hit after losing a life.
+pub const PLAYER_INVULNERABILITY_SECONDS: f32 = 2.0;
+const PLAYER_BLINK_SECONDS: f32 = 0.1;
+pub const PLAYER_DASH_SPEED: f32 = 1200.0;
+/// How long a dash lasts, the player can not be hit during it.
+pub const PLAYER_DASH_SECONDS: f32 = 0.15;
+pub const PLAYER_DASH_COOLDOWN_SECONDS: f32 = 1.5;
+/// Factor on the speed and acceleration of the ship while the player focuses.
+pub const FOCUS_SPEED_FACTOR: f32 = 0.4;
+const FOCUS_HITBOX_COLOR: Color = Color::srgb(5.0, 5.0, 5.0);
+pub const MAX_PLAYERS: usize = 4;
+const PLAYER_COLORS: [Color; MAX_PLAYERS] = [
+    Color::srgb(0.0, 0.0, 5.0),
+    Color::srgb(5.0, 5.0, 0.0),
+    Color::srgb(5.0, 0.0, 5.0),
+    Color::srgb(4.0, 4.0, 4.0),
+];
+/// Horizontal distance between players at the start of a co-op run.
+const PLAYER_SPACING: f32 = 60.0;
 
 pub struct PlayerPlugin;
 
@@ -15,81 +37,365 @@ impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             FixedUpdate,
-            (player_movement, clamp_player, collide)
-                .chain()
-                .run_if(in_state(State::Playing)),
+            (
+                (
+                    end_dash,
+                    dash,
+                    player_movement,
+                    blink_invulnerable,
+                    show_focus_hitbox,
+                )
+                    .chain()
+                    .in_set(GameplaySet::Player),
+                (clamp_player, collide)
+                    .chain()
+                    .in_set(GameplaySet::Collision),
+            ),
         )
-        .add_systems(OnEnter(State::Playing), spawn_player);
+        .init_resource::<CauseOfDeath>()
+        .init_resource::<PlayerCount>()
+        .add_event::<PlayerHit>()
+        .add_systems(
+            OnEnter(State::Starting),
+            (despawn_players, spawn_player).chain(),
+        )
+        .add_systems(OnEnter(State::MainMenu), despawn_players);
     }
 }
 
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Player {
+    /// Position among the players of the run, from 0 up to [PlayerCount].
+    pub index: usize,
+}
+
+impl Player {
+    pub fn color(&self) -> Color {
+        PLAYER_COLORS[self.index % MAX_PLAYERS]
+    }
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+/// How many players the next run starts with, from 1 for single player up to [MAX_PLAYERS].
+pub struct PlayerCount(pub usize);
+
+impl Default for PlayerCount {
+    fn default() -> Self {
+        PlayerCount(1)
+    }
+}
+
+impl PlayerCount {
+    /// Whether a run can start with this many players.
+    pub fn is_valid(&self) -> bool {
+        (1..=MAX_PLAYERS).contains(&self.0)
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Health {
+    /// Hits the player can still take, the run ends when this reaches zero.
+    pub hits_left: u32,
+}
+
 #[derive(Component)]
-pub struct Player;
+/// The player blinks and can not be hit until the timer finishes.
+pub struct Invulnerable(Timer);
 
-fn spawn_player(mut commands: Commands, player_entity: Option<Single<Entity, With<Player>>>) {
-    if let Some(entity) = player_entity {
-        commands.entity(*entity).despawn();
+#[derive(Component)]
+pub struct Dash {
+    cooldown: Timer,
+    /// Whether the dash button was held last tick.
+    was_pressed: bool,
+}
+
+impl Dash {
+    fn new() -> Self {
+        let mut cooldown = Timer::from_seconds(PLAYER_DASH_COOLDOWN_SECONDS, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Dash {
+            cooldown,
+            was_pressed: false,
+        }
+    }
+
+    /// Seconds until the player can dash again, zero when the dash is ready.
+    pub fn cooldown_remaining(&self) -> f32 {
+        self.cooldown.remaining_secs()
     }
+}
+
+#[derive(Component)]
+/// Shows the true hitbox of the player while they focus.
+struct FocusHitbox;
+
+#[derive(Component)]
+/// The player is dashing: acceleration is suspended and enemies can not hit.
+pub struct Dashing(Timer);
+
+#[derive(Event, Debug)]
+/// A player ran into an enemy, sent for the hit that takes their last life as well.
+pub struct PlayerHit {
+    pub player: usize,
+    pub enemy: Enemy,
+}
+
+#[derive(Resource, Default, Debug)]
+/// The enemy that took the last life of the last player standing, `None` while a player is alive.
+pub struct CauseOfDeath(pub Option<Enemy>);
 
-    commands.spawn((
-        Sprite::from_color(
-            Color::srgb(0.0, 0.0, 5.0),
-            Vec2::new(PLAYER_SIZE, PLAYER_SIZE),
-        ),
-        Velocity::new(Vec3::ZERO, PLAYER_MAX_SPEED),
-        Acceleration::default(),
-        Player,
-    ));
+fn despawn_players(mut commands: Commands, player_entities: Query<Entity, With<Player>>) {
+    for entity in player_entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 }
 
-fn player_movement(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut acceleration: Single<&mut Acceleration, With<Player>>,
+fn spawn_player(
+    mut commands: Commands,
+    mut cause_of_death: ResMut<CauseOfDeath>,
+    mode: Res<GameMode>,
+    player_count: Res<PlayerCount>,
+    ship_selection: Res<ShipSelection>,
 ) {
-    let mut input_vector = Vec3::ZERO;
-    if keyboard_input.pressed(KeyCode::ArrowLeft) {
-        input_vector.x -= 1.0;
+    cause_of_death.0 = None;
+
+    let count = player_count.0;
+    for index in 0..count {
+        let player = Player { index };
+        let x = (index as f32 - 0.5 * (count - 1) as f32) * PLAYER_SPACING;
+        let stats = ship_selection.0[index].stats();
+        commands
+            .spawn((
+                Sprite::from_color(player.color(), Vec2::splat(stats.size)),
+                Transform::from_xyz(x, 0.0, 0.0),
+                Velocity::new(Vec3::ZERO, stats.max_speed),
+                stats,
+                Acceleration::default(),
+                PlayerInput::default(),
+                Health {
+                    hits_left: mode.player_hits(),
+                },
+                Dash::new(),
+                Shockwave::default(),
+                player,
+            ))
+            .with_child((
+                FocusHitbox,
+                Sprite::from_color(FOCUS_HITBOX_COLOR, Vec2::splat(stats.hitbox_size(true))),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+                Visibility::Hidden,
+            ));
     }
-    if keyboard_input.pressed(KeyCode::ArrowRight) {
-        input_vector.x += 1.0;
+}
+
+fn end_dash(mut commands: Commands, mut dashing: Query<(Entity, &mut Dashing)>, time: Res<Time>) {
+    for (entity, mut dashing) in dashing.iter_mut() {
+        if dashing.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Dashing>();
+        }
+    }
+}
+
+fn dash(
+    mut commands: Commands,
+    mut players: Query<(Entity, &PlayerInput, &mut Dash, &mut Velocity), With<Player>>,
+    time: Res<Time>,
+) {
+    for (entity, input, mut dash, mut velocity) in players.iter_mut() {
+        dash.cooldown.tick(time.delta());
+
+        let pressed = input.dash && !dash.was_pressed;
+        dash.was_pressed = input.dash;
+        if !pressed || !dash.cooldown.finished() {
+            continue;
+        }
+        // Dash where the player is steering, or keep going the way they drift.
+        let Some(direction) = input
+            .movement
+            .try_normalize()
+            .or_else(|| velocity.value.xy().try_normalize())
+        else {
+            continue;
+        };
+
+        velocity.value = (direction * PLAYER_DASH_SPEED).extend(0.0);
+        dash.cooldown.reset();
+        commands.entity(entity).insert(Dashing(Timer::from_seconds(
+            PLAYER_DASH_SECONDS,
+            TimerMode::Once,
+        )));
     }
-    if keyboard_input.pressed(KeyCode::ArrowUp) {
-        input_vector.y += 1.0;
+}
+
+/// What [player_movement] needs to know about a player.
+type PlayerHandling = (
+    &'static PlayerInput,
+    &'static ShipStats,
+    &'static mut Acceleration,
+    &'static mut Velocity,
+    Has<Dashing>,
+);
+
+fn player_movement(mut players: Query<PlayerHandling, With<Player>>) {
+    for (input, stats, mut acceleration, mut velocity, dashing) in players.iter_mut() {
+        let speed_factor = if input.focus { FOCUS_SPEED_FACTOR } else { 1.0 };
+        velocity.set_max(stats.max_speed * speed_factor);
+        acceleration.direction = input.movement.extend(0.0);
+        if dashing {
+            acceleration.amount = 0.0;
+        } else if input.movement.x.abs() > 0.0 || input.movement.y.abs() > 0.0 {
+            acceleration.amount = stats.acceleration * speed_factor;
+        } else {
+            acceleration.amount = stats.drag;
+        }
+    }
+}
+
+fn show_focus_hitbox(
+    players: Query<&PlayerInput, With<Player>>,
+    mut hitboxes: Query<(&Parent, &mut Visibility), With<FocusHitbox>>,
+) {
+    for (parent, mut visibility) in hitboxes.iter_mut() {
+        let focused = players.get(parent.get()).is_ok_and(|input| input.focus);
+        visibility.set_if_neq(if focused {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
     }
-    if keyboard_input.pressed(KeyCode::ArrowDown) {
-        input_vector.y -= 1.0;
+}
+
+fn clamp_player(mut players: Query<(&mut Transform, &ShipStats), With<Player>>) {
+    for (mut player_transform, stats) in players.iter_mut() {
+        let boundary = stats.boundary();
+        player_transform.translation = player_transform.translation.clamp(-boundary, boundary);
     }
+}
 
-    input_vector = input_vector.normalize_or_zero();
-    acceleration.direction = input_vector;
-    if input_vector.x.abs() > 0.0 || input_vector.y.abs() > 0.0 {
-        acceleration.amount = PLAYER_ACCELERATION;
-    } else {
-        acceleration.amount = PLAYER_DRAG;
+fn blink_invulnerable(
+    mut commands: Commands,
+    mut players: Query<(Entity, &mut Invulnerable, &mut Visibility), With<Player>>,
+    time: Res<Time>,
+) {
+    for (entity, mut invulnerable, mut visibility) in players.iter_mut() {
+        if invulnerable.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+            *visibility = Visibility::Inherited;
+            continue;
+        }
+        let blinks = (invulnerable.0.elapsed_secs() / PLAYER_BLINK_SECONDS) as u32;
+        *visibility = if blinks.is_multiple_of(2) {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
     }
 }
 
-fn clamp_player(mut player_transform: Single<&mut Transform, With<Player>>) {
-    player_transform.translation = player_transform
-        .translation
-        .clamp(-WORLD_BOUNDARY_VECTOR, WORLD_BOUNDARY_VECTOR);
+/// Players that enemies can hit right now.
+type Vulnerable = (With<Player>, Without<Invulnerable>, Without<Dashing>);
+
+/// What [collide] needs to know about a player.
+type PlayerHitbox = (
+    Entity,
+    &'static Player,
+    &'static Transform,
+    &'static ShipStats,
+    &'static PlayerInput,
+    &'static mut Health,
+    Has<Shield>,
+);
+
+/// What [Hazards] needs to know about an enemy.
+type EnemyHitbox = (
+    Entity,
+    &'static Transform,
+    &'static Hitbox,
+    &'static Enemy,
+    Has<Boss>,
+);
+
+#[derive(SystemParam)]
+/// Everything in the arena that can hit a player.
+pub struct Hazards<'w, 's> {
+    enemies: Query<'w, 's, EnemyHitbox, Without<Unarmed>>,
+    beams: Query<'w, 's, &'static LaserBeam>,
+}
+
+impl Hazards<'_, '_> {
+    /// The first enemy or firing beam that touches `aabb`, leaving out the enemies in `ignored`.
+    /// Only enemies that are used up by the hit come with their entity, bosses and beams are not.
+    fn hit(&self, aabb: &Aabb2d, ignored: &[Entity]) -> Option<(Option<Entity>, Enemy)> {
+        self.enemies
+            .iter()
+            .find(|(entity, transform, hitbox, _, _)| {
+                !ignored.contains(entity)
+                    && aabb.intersects(&Aabb2d::new(transform.translation.xy(), 0.5 * hitbox.0))
+            })
+            .map(|(entity, _, _, enemy, boss)| ((!boss).then_some(entity), *enemy))
+            .or_else(|| {
+                self.beams
+                    .iter()
+                    .find(|beam| beam.hits(aabb))
+                    .map(|beam| (None, beam.enemy()))
+            })
+    }
 }
 
+/// Takes a life from every player an enemy runs into. A player without lives left is
+/// despawned, and the run ends when no player is left.
 fn collide(
+    mut commands: Commands,
     mut nextstate: ResMut<NextState<State>>,
-    player_transform: Single<&Transform, With<Player>>,
-    enemy_transforms: Query<(&Transform, &Enemy)>,
+    mut cause_of_death: ResMut<CauseOfDeath>,
+    mut player_hit_events: EventWriter<PlayerHit>,
+    mut players: Query<PlayerHitbox, Vulnerable>,
+    all_players: Query<(), With<Player>>,
+    hazards: Hazards,
 ) {
-    for (enemy_transform, enemy) in enemy_transforms.iter() {
-        let player_aabb = Aabb2d::new(
-            player_transform.translation.xy(),
-            Vec2::new(0.5 * PLAYER_SIZE, 0.5 * PLAYER_SIZE),
-        );
-        let enemy_aabb = Aabb2d::new(
-            enemy_transform.translation.xy(),
-            Vec2::new(0.5 * enemy.size(), 0.5 * enemy.size()),
-        );
-        if player_aabb.intersects(&enemy_aabb) {
+    let mut players_left = all_players.iter().count();
+    // An enemy is despawned by the hit, so it can only hit one player.
+    let mut hit_enemies = Vec::new();
+    for (player_entity, player, player_transform, stats, input, mut health, shielded) in
+        players.iter_mut()
+    {
+        let player_aabb = stats.hitbox(player_transform, input.focus);
+        // One hit per tick, the rest is covered by the invulnerability.
+        let Some((enemy_entity, enemy)) = hazards.hit(&player_aabb, &hit_enemies) else {
+            continue;
+        };
+
+        if let Some(enemy_entity) = enemy_entity {
+            hit_enemies.push(enemy_entity);
+            commands.entity(enemy_entity).despawn();
+        }
+        // Beams and bosses are still there next tick, so surviving a hit also needs the
+        // invulnerability to get away from them.
+        let invulnerable = Invulnerable(Timer::from_seconds(
+            PLAYER_INVULNERABILITY_SECONDS,
+            TimerMode::Once,
+        ));
+        if shielded {
+            commands
+                .entity(player_entity)
+                .remove::<Shield>()
+                .insert(invulnerable);
+            continue;
+        }
+        player_hit_events.send(PlayerHit {
+            player: player.index,
+            enemy,
+        });
+        health.hits_left = health.hits_left.saturating_sub(1);
+        if health.hits_left > 0 {
+            commands.entity(player_entity).insert(invulnerable);
+            continue;
+        }
+
+        commands.entity(player_entity).despawn_recursive();
+        players_left -= 1;
+        if players_left == 0 {
+            cause_of_death.0 = Some(enemy);
             nextstate.set(State::GameOver);
         }
     }
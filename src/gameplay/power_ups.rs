@@ -0,0 +1,233 @@
+use crate::gameplay::GameplaySet;
+use crate::gameplay::enemies::{Boss, Enemy, EnemyDestroyed};
+use crate::gameplay::movement::MovementScale;
+use crate::gameplay::player::Player;
+use crate::gameplay::rng::PowerUpRng;
+use crate::gameplay::ships::ShipStats;
+use crate::world::{State, WORLD_SIZE, arena_boundary};
+use bevy::math::bounding::{Aabb2d, IntersectsVolume};
+use bevy::prelude::*;
+use rand::Rng;
+
+pub const POWER_UP_SIZE: f32 = 16.0;
+/// Seconds between two pickups appearing in the arena.
+const POWER_UP_SPAWN_SECONDS: f32 = 12.0;
+/// Seconds a pickup waits to be collected before it disappears.
+const POWER_UP_LIFETIME_SECONDS: f32 = 8.0;
+pub const SLOW_TIME_SECONDS: f32 = 5.0;
+/// Factor on the velocity of every enemy while time is slowed.
+const SLOW_TIME_FACTOR: f32 = 0.5;
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PowerUpSpawnTimer(Timer::from_seconds(
+            POWER_UP_SPAWN_SECONDS,
+            TimerMode::Repeating,
+        )))
+        .init_resource::<SlowTime>()
+        .add_systems(
+            FixedUpdate,
+            (
+                spawn_power_ups,
+                expire_power_ups,
+                collect_power_ups,
+                slow_enemies,
+            )
+                .chain()
+                .in_set(GameplaySet::PowerUps),
+        )
+        .add_systems(OnEnter(State::Starting), reset_power_ups)
+        .add_systems(OnEnter(State::MainMenu), reset_power_ups);
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerUp {
+    /// Absorbs the next hit.
+    Shield,
+    /// Halves the speed of every enemy for [SLOW_TIME_SECONDS].
+    SlowTime,
+    /// Destroys every enemy in the arena.
+    Bomb,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 3] = [PowerUp::Shield, PowerUp::SlowTime, PowerUp::Bomb];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerUp::Shield => "Shield",
+            PowerUp::SlowTime => "Slow time",
+            PowerUp::Bomb => "Bomb",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            PowerUp::Shield => Color::srgb(0.0, 3.0, 3.0),
+            PowerUp::SlowTime => Color::srgb(2.0, 0.0, 3.0),
+            PowerUp::Bomb => Color::srgb(3.0, 1.5, 0.0),
+        }
+    }
+}
+
+#[derive(Component)]
+/// The next enemy that hits the player is destroyed instead.
+pub struct Shield;
+
+#[derive(Component)]
+/// Time left for a pickup to be collected.
+struct PickupLifetime(Timer);
+
+#[derive(Resource)]
+struct PowerUpSpawnTimer(Timer);
+
+#[derive(Resource, Default)]
+/// Counts down while [PowerUp::SlowTime] is active.
+pub struct SlowTime(Option<Timer>);
+
+impl SlowTime {
+    /// Seconds until enemies are back to full speed, `None` when time is not slowed.
+    pub fn remaining_secs(&self) -> Option<f32> {
+        self.0.as_ref().map(Timer::remaining_secs)
+    }
+}
+
+fn reset_power_ups(
+    mut commands: Commands,
+    mut timer: ResMut<PowerUpSpawnTimer>,
+    mut slow_time: ResMut<SlowTime>,
+    pickups: Query<Entity, With<PowerUp>>,
+) {
+    for entity in pickups.iter() {
+        commands.entity(entity).despawn();
+    }
+    timer.0.reset();
+    slow_time.0 = None;
+}
+
+fn spawn_power_ups(
+    mut commands: Commands,
+    mut timer: ResMut<PowerUpSpawnTimer>,
+    mut rng: ResMut<PowerUpRng>,
+    time: Res<Time>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let power_up = PowerUp::ALL[rng.random_range(0..PowerUp::ALL.len())];
+    let boundary = arena_boundary(POWER_UP_SIZE);
+    let position = Vec2::new(
+        rng.random_range(-boundary.x..=boundary.x),
+        rng.random_range(-boundary.y..=boundary.y),
+    );
+    commands.spawn((
+        Sprite::from_color(power_up.color(), Vec2::splat(POWER_UP_SIZE)),
+        Transform::from_translation(position.extend(0.0)),
+        PickupLifetime(Timer::from_seconds(
+            POWER_UP_LIFETIME_SECONDS,
+            TimerMode::Once,
+        )),
+        power_up,
+    ));
+}
+
+fn expire_power_ups(
+    mut commands: Commands,
+    mut pickups: Query<(Entity, &mut PickupLifetime)>,
+    time: Res<Time>,
+) {
+    for (entity, mut lifetime) in pickups.iter_mut() {
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn collect_power_ups(
+    mut commands: Commands,
+    mut slow_time: ResMut<SlowTime>,
+    mut destroyed_events: EventWriter<EnemyDestroyed>,
+    players: Query<(Entity, &Player, &Transform, &ShipStats)>,
+    pickups: Query<(Entity, &Transform, &PowerUp)>,
+    enemies: Query<(Entity, &Transform, &Enemy), Without<Boss>>,
+    mut bosses: Query<(&Transform, &mut Boss)>,
+) {
+    // A pickup or enemy is despawned at the end of the tick, so it must not be counted twice.
+    let mut collected = Vec::new();
+    let mut bombed = Vec::new();
+    for (player_entity, player, player_transform, stats) in players.iter() {
+        let player_aabb = stats.aabb(player_transform);
+        for (pickup_entity, pickup_transform, power_up) in pickups.iter() {
+            if collected.contains(&pickup_entity) {
+                continue;
+            }
+            let pickup_aabb = Aabb2d::new(
+                pickup_transform.translation.xy(),
+                Vec2::splat(0.5 * POWER_UP_SIZE),
+            );
+            if !player_aabb.intersects(&pickup_aabb) {
+                continue;
+            }
+            collected.push(pickup_entity);
+            commands.entity(pickup_entity).despawn();
+            debug!(?power_up, player = player.index, "Power-up collected");
+            match power_up {
+                PowerUp::Shield => {
+                    commands.entity(player_entity).insert(Shield);
+                }
+                PowerUp::SlowTime => {
+                    slow_time.0 = Some(Timer::from_seconds(SLOW_TIME_SECONDS, TimerMode::Once));
+                }
+                PowerUp::Bomb => {
+                    let arena = Vec2::splat(0.5 * WORLD_SIZE);
+                    for (enemy_entity, enemy_transform, enemy) in enemies.iter() {
+                        if !bombed.contains(&enemy_entity)
+                            && enemy_transform.translation.xy().abs().cmple(arena).all()
+                        {
+                            bombed.push(enemy_entity);
+                            commands.entity(enemy_entity).despawn();
+                            destroyed_events.send(EnemyDestroyed {
+                                player: player.index,
+                                enemy: *enemy,
+                                position: enemy_transform.translation,
+                            });
+                        }
+                    }
+                    for (boss_transform, mut boss) in bosses.iter_mut() {
+                        if boss_transform.translation.xy().abs().cmple(arena).all() {
+                            boss.hit();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Keeps every enemy slowed while [SlowTime] runs, including the ones spawned meanwhile.
+fn slow_enemies(
+    mut commands: Commands,
+    mut slow_time: ResMut<SlowTime>,
+    unslowed: Query<Entity, (With<Enemy>, Without<MovementScale>)>,
+    slowed: Query<Entity, (With<Enemy>, With<MovementScale>)>,
+    time: Res<Time>,
+) {
+    let Some(timer) = slow_time.0.as_mut() else {
+        return;
+    };
+    if timer.tick(time.delta()).finished() {
+        slow_time.0 = None;
+        for entity in slowed.iter() {
+            commands.entity(entity).remove::<MovementScale>();
+        }
+        return;
+    }
+    for entity in unslowed.iter() {
+        commands
+            .entity(entity)
+            .insert(MovementScale(SLOW_TIME_FACTOR));
+    }
+}
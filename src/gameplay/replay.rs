@@ -0,0 +1,182 @@
+use crate::gameplay::GameplaySet;
+use crate::gameplay::enemies::WaveScriptPath;
+use crate::gameplay::input::{PlayerInput, PlayerInputSet};
+use crate::gameplay::mode::GameMode;
+use crate::gameplay::player::{MAX_PLAYERS, Player, PlayerCount};
+use crate::gameplay::ships::ShipSelection;
+use crate::world::State;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::Path;
+
+/// Bump this whenever [Replay] or [PlayerInput] changes shape,
+/// or gameplay changes so that recorded inputs no longer play out the same run.
+pub const REPLAY_VERSION: u32 = 1;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayRecorder>()
+            .insert_resource(SaveReplays(true))
+            .add_systems(
+                FixedUpdate,
+                (
+                    play_back_inputs
+                        .in_set(PlayerInputSet::Override)
+                        .run_if(resource_exists::<ReplayPlayback>),
+                    record_inputs
+                        .after(PlayerInputSet::Override)
+                        .in_set(GameplaySet::Input),
+                ),
+            )
+            .add_systems(OnEnter(State::Starting), reset_replay);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(
+            OnEnter(State::GameOver),
+            save_replay
+                .run_if(|save: Res<SaveReplays>| save.0)
+                .run_if(not(resource_exists::<ReplayPlayback>)),
+        );
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+/// Everything needed to play a run back: the seed and the input of every [FixedUpdate] tick.
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    /// The [GameMode] of the run.
+    pub mode: GameMode,
+    /// The [PlayerCount] of the run.
+    pub players: usize,
+    /// The [ShipSelection] of the run.
+    pub ships: ShipSelection,
+    /// The [WaveScriptPath] of the run.
+    pub wave_script: Option<String>,
+    /// The input of every player, by [Player::index], for each tick.
+    pub inputs: Vec<Vec<PlayerInput>>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Replay, Box<dyn Error>> {
+        let replay: Replay = ron::from_str(&std::fs::read_to_string(path)?)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(format!(
+                "replay has version {} but this build only plays version {}",
+                replay.version, REPLAY_VERSION
+            )
+            .into());
+        }
+        if !PlayerCount(replay.players).is_valid() {
+            return Err(format!(
+                "replay has {} players but a run has 1 to {MAX_PLAYERS}",
+                replay.players
+            )
+            .into());
+        }
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, ron::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+/// Whether a replay is written to disk on game over.
+pub struct SaveReplays(pub bool);
+
+#[derive(Resource, Default)]
+/// Input of the current run so far.
+struct ReplayRecorder {
+    inputs: Vec<Vec<PlayerInput>>,
+}
+
+#[derive(Resource)]
+/// When present, the player is driven by this replay instead of by input devices.
+/// The [crate::gameplay::rng::SeedConfig], [GameMode], [PlayerCount], [ShipSelection] and
+/// [WaveScriptPath] must be set to those of the replay as well.
+pub struct ReplayPlayback {
+    replay: Replay,
+    tick: usize,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        ReplayPlayback { replay, tick: 0 }
+    }
+}
+
+fn reset_replay(mut recorder: ResMut<ReplayRecorder>, playback: Option<ResMut<ReplayPlayback>>) {
+    recorder.inputs.clear();
+    if let Some(mut playback) = playback {
+        playback.tick = 0;
+    }
+}
+
+fn play_back_inputs(
+    mut playback: ResMut<ReplayPlayback>,
+    mut players: Query<(&Player, &mut PlayerInput)>,
+) {
+    let inputs = playback.replay.inputs.get(playback.tick);
+    for (player, mut player_input) in players.iter_mut() {
+        *player_input = inputs
+            .and_then(|inputs| inputs.get(player.index))
+            .copied()
+            .unwrap_or_default();
+    }
+    playback.tick += 1;
+}
+
+/// Records the input of every player, players that are out record no input.
+fn record_inputs(
+    mut recorder: ResMut<ReplayRecorder>,
+    player_count: Res<PlayerCount>,
+    players: Query<(&Player, &PlayerInput)>,
+) {
+    let mut inputs = vec![PlayerInput::default(); player_count.0];
+    for (player, player_input) in players.iter() {
+        if let Some(input) = inputs.get_mut(player.index) {
+            *input = *player_input;
+        }
+    }
+    recorder.inputs.push(inputs);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_replay(
+    recorder: Res<ReplayRecorder>,
+    seed: Res<crate::gameplay::rng::RunSeed>,
+    mode: Res<GameMode>,
+    player_count: Res<PlayerCount>,
+    ships: Res<ShipSelection>,
+    wave_script: Res<WaveScriptPath>,
+) {
+    let replay = Replay {
+        version: REPLAY_VERSION,
+        seed: seed.0,
+        mode: *mode,
+        players: player_count.0,
+        ships: *ships,
+        wave_script: wave_script.0.clone(),
+        inputs: recorder.inputs.clone(),
+    };
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let path = crate::storage::data_dir()
+        .join("replays")
+        .join(format!("replay-{timestamp}-{}.ron", seed.0));
+    match replay.save(&path) {
+        Ok(()) => info!("Saved replay to {}", path.display()),
+        Err(error) => error!("Could not save replay to {}: {error}", path.display()),
+    }
+}
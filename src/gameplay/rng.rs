@@ -0,0 +1,61 @@
+use crate::world::State;
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SeedConfig>()
+            .insert_resource(RunSeed(0))
+            .insert_resource(EnemyRng(stream(0, RngStream::Enemies)))
+            .insert_resource(PowerUpRng(stream(0, RngStream::PowerUps)))
+            .add_systems(OnEnter(State::Starting), reseed);
+    }
+}
+
+#[derive(Resource, Default, Clone, Copy, Debug)]
+/// Seed to use for every run. When `None` a fresh seed is drawn each time a run starts.
+/// Insert this on the [App] to reproduce a run.
+pub struct SeedConfig(pub Option<u64>);
+
+#[derive(Resource, Clone, Copy, Debug)]
+/// The seed the current run was started with.
+/// Together with the player input, the seed fully determines a run.
+pub struct RunSeed(pub u64);
+
+#[derive(Resource, Deref, DerefMut)]
+/// Randomness for spawning enemies.
+pub struct EnemyRng(ChaCha8Rng);
+
+#[derive(Resource, Deref, DerefMut)]
+/// Randomness for spawning power-ups.
+pub struct PowerUpRng(ChaCha8Rng);
+
+#[derive(Clone, Copy)]
+/// Each part of the gameplay that draws random numbers gets its own stream of the seed, so what
+/// one part draws does not depend on how often or in which order the others draw.
+enum RngStream {
+    Enemies,
+    PowerUps,
+}
+
+fn stream(seed: u64, stream: RngStream) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(stream as u64);
+    rng
+}
+
+fn reseed(
+    seed_config: Res<SeedConfig>,
+    mut run_seed: ResMut<RunSeed>,
+    mut enemy_rng: ResMut<EnemyRng>,
+    mut power_up_rng: ResMut<PowerUpRng>,
+) {
+    let seed = seed_config.0.unwrap_or_else(|| rand::rng().random());
+    run_seed.0 = seed;
+    enemy_rng.0 = stream(seed, RngStream::Enemies);
+    power_up_rng.0 = stream(seed, RngStream::PowerUps);
+    info!("Starting run with seed {seed}");
+}
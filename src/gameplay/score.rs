@@ -1,3 +1,6 @@
+use crate::gameplay::GameplaySet;
+use crate::gameplay::enemies::{BossDefeated, EnemyDefinitions, EnemyDestroyed};
+use crate::gameplay::player::{Player, PlayerCount};
 use crate::world::State;
 use bevy::prelude::*;
 use std::time::Duration;
@@ -11,18 +14,36 @@ impl Plugin for ScorePlugin {
             TimerMode::Repeating,
         )))
         .insert_resource(Score(0))
+        .init_resource::<PlayerScores>()
         .add_event::<ScoreIncreasedEvent>()
-        .add_systems(FixedUpdate, update_score.run_if(in_state(State::Playing)))
-        .add_systems(OnEnter(State::Playing), reset_score);
+        .add_systems(
+            FixedUpdate,
+            (update_score, score_destroyed_enemies, score_defeated_bosses)
+                .chain()
+                .in_set(ScoreSet),
+        )
+        .configure_sets(FixedUpdate, ScoreSet.in_set(GameplaySet::Progress))
+        .add_systems(OnEnter(State::Starting), reset_score);
     }
 }
 
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// The systems that change the [Score], systems that react to the score go after it.
+pub struct ScoreSet;
+
 #[derive(Resource)]
 struct ScoreTimer(Timer);
 
 #[derive(Resource)]
+/// Score of the whole run: a point per second while any player is in, plus every destroyed enemy
+/// and defeated boss.
 pub struct Score(pub u32);
 
+#[derive(Resource, Default, Debug)]
+/// Score of each player by [Player::index]: a point per second they stayed in,
+/// plus the enemies they destroyed and the bonus of the bosses defeated while they were in.
+pub struct PlayerScores(pub Vec<u32>);
+
 #[derive(Event)]
 pub struct ScoreIncreasedEvent(u32);
 
@@ -32,8 +53,14 @@ impl ScoreIncreasedEvent {
     }
 }
 
-fn reset_score(mut score: ResMut<Score>, mut timer: ResMut<ScoreTimer>) {
+fn reset_score(
+    mut score: ResMut<Score>,
+    mut player_scores: ResMut<PlayerScores>,
+    mut timer: ResMut<ScoreTimer>,
+    player_count: Res<PlayerCount>,
+) {
     score.0 = 0;
+    player_scores.0 = vec![0; player_count.0];
     timer.0.reset();
 }
 
@@ -41,10 +68,60 @@ fn update_score(
     mut score_event: EventWriter<ScoreIncreasedEvent>,
     mut score_timer: ResMut<ScoreTimer>,
     mut score: ResMut<Score>,
+    mut player_scores: ResMut<PlayerScores>,
+    players: Query<&Player>,
     time: Res<Time>,
 ) {
     if score_timer.0.tick(time.delta()).finished() {
+        for player in players.iter() {
+            if let Some(player_score) = player_scores.0.get_mut(player.index) {
+                *player_score += 1;
+            }
+        }
         score.0 += 1;
         score_event.send(ScoreIncreasedEvent(score.0));
     }
 }
+
+/// Awards the points of every enemy the player destroyed.
+fn score_destroyed_enemies(
+    mut destroyed_events: EventReader<EnemyDestroyed>,
+    mut score_event: EventWriter<ScoreIncreasedEvent>,
+    mut score: ResMut<Score>,
+    mut player_scores: ResMut<PlayerScores>,
+    definitions: Res<EnemyDefinitions>,
+) {
+    let mut points = 0;
+    for destroyed in destroyed_events.read() {
+        let enemy_points = definitions.get(destroyed.enemy).points;
+        if let Some(player_score) = player_scores.0.get_mut(destroyed.player) {
+            *player_score += enemy_points;
+        }
+        points += enemy_points;
+    }
+    if points > 0 {
+        score.0 += points;
+        score_event.send(ScoreIncreasedEvent(score.0));
+    }
+}
+
+/// Awards the bonus of every defeated boss to the run and to every player that is still in.
+fn score_defeated_bosses(
+    mut defeated_events: EventReader<BossDefeated>,
+    mut score_event: EventWriter<ScoreIncreasedEvent>,
+    mut score: ResMut<Score>,
+    mut player_scores: ResMut<PlayerScores>,
+    players: Query<&Player>,
+) {
+    let bonus: u32 = defeated_events.read().map(|defeated| defeated.bonus).sum();
+    if bonus == 0 {
+        return;
+    }
+    for player in players.iter() {
+        if let Some(player_score) = player_scores.0.get_mut(player.index) {
+            *player_score += bonus;
+        }
+    }
+    score.0 += bonus;
+    score_event.send(ScoreIncreasedEvent(score.0));
+}
@@ -0,0 +1,143 @@
+use crate::gameplay::player::MAX_PLAYERS;
+use crate::world::arena_boundary;
+use bevy::math::bounding::Aabb2d;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Factor on the size of the hitbox while the player focuses.
+pub const FOCUS_HITBOX_FACTOR: f32 = 0.5;
+
+pub struct ShipPlugin;
+
+impl Plugin for ShipPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShipSelection>();
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+/// A ship from the roster the players pick from before a run.
+pub enum Ship {
+    #[default]
+    /// The all-rounder.
+    Standard,
+    /// Small and fast, but slow to turn.
+    Dart,
+    /// A big target that stops and turns on a dime.
+    Brick,
+    /// Quick, but keeps sliding long after letting go.
+    Drifter,
+}
+
+impl Ship {
+    pub const ALL: [Ship; 4] = [Ship::Standard, Ship::Dart, Ship::Brick, Ship::Drifter];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ship::Standard => "Standard",
+            Ship::Dart => "Dart",
+            Ship::Brick => "Brick",
+            Ship::Drifter => "Drifter",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Ship::Standard => "the all-rounder",
+            Ship::Dart => "small and fast, slow to turn",
+            Ship::Brick => "large and grippy",
+            Ship::Drifter => "quick, but slides",
+        }
+    }
+
+    pub fn stats(&self) -> ShipStats {
+        match self {
+            Ship::Standard => ShipStats {
+                max_speed: 300.0,
+                acceleration: 50.0,
+                drag: 50.0,
+                size: 20.0,
+            },
+            Ship::Dart => ShipStats {
+                max_speed: 380.0,
+                acceleration: 25.0,
+                drag: 25.0,
+                size: 14.0,
+            },
+            Ship::Brick => ShipStats {
+                max_speed: 260.0,
+                acceleration: 120.0,
+                drag: 120.0,
+                size: 28.0,
+            },
+            Ship::Drifter => ShipStats {
+                max_speed: 340.0,
+                acceleration: 20.0,
+                drag: 4.0,
+                size: 20.0,
+            },
+        }
+    }
+
+    /// The ship after this one in the roster, going round to the first after the last.
+    pub fn next(&self) -> Ship {
+        let position = Ship::ALL.iter().position(|ship| ship == self).unwrap_or(0);
+        Ship::ALL[(position + 1) % Ship::ALL.len()]
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+/// How a player's ship handles, see [Ship::stats].
+pub struct ShipStats {
+    pub max_speed: f32,
+    /// How quickly the ship speeds up towards where it is steered.
+    pub acceleration: f32,
+    /// How quickly the ship slows down when it is not steered.
+    pub drag: f32,
+    /// Width and height of the ship, and of its collision box.
+    pub size: f32,
+}
+
+impl ShipStats {
+    /// The box of the whole ship, that pickups are checked against.
+    pub fn aabb(&self, transform: &Transform) -> Aabb2d {
+        Aabb2d::new(transform.translation.xy(), Vec2::splat(0.5 * self.size))
+    }
+
+    /// Width and height of the box enemies are checked against, which shrinks while focused.
+    pub fn hitbox_size(&self, focused: bool) -> f32 {
+        if focused {
+            FOCUS_HITBOX_FACTOR * self.size
+        } else {
+            self.size
+        }
+    }
+
+    /// The box that enemies are checked against.
+    pub fn hitbox(&self, transform: &Transform, focused: bool) -> Aabb2d {
+        Aabb2d::new(
+            transform.translation.xy(),
+            Vec2::splat(0.5 * self.hitbox_size(focused)),
+        )
+    }
+
+    /// How far from the center the ship can go while staying fully inside the arena.
+    pub fn boundary(&self) -> Vec3 {
+        arena_boundary(self.size)
+    }
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+/// The ship each player, by [crate::gameplay::player::Player::index], starts the next run with.
+pub struct ShipSelection(pub [Ship; MAX_PLAYERS]);
+
+impl ShipSelection {
+    /// Makes `ship` the ship of `player` for the next run.
+    pub fn select(player: usize, ship: Ship, commands: &mut Commands) {
+        commands.queue(move |world: &mut World| {
+            if let Some(selected) = world.resource_mut::<ShipSelection>().0.get_mut(player) {
+                *selected = ship;
+            }
+        });
+    }
+}
@@ -0,0 +1,175 @@
+use crate::gameplay::GameplaySet;
+use crate::gameplay::enemies::{Boss, Enemy, EnemyDestroyed, Hitbox, Unarmed};
+use crate::gameplay::input::PlayerInput;
+use crate::gameplay::player::Player;
+use crate::gameplay::ships::ShipStats;
+use crate::world::State;
+use bevy::math::bounding::{Aabb2d, BoundingVolume, IntersectsVolume};
+use bevy::prelude::*;
+
+/// Enemies whose center is this close to the player are destroyed by a shockwave.
+pub const SHOCKWAVE_RADIUS: f32 = 200.0;
+/// Seconds of survival that charge the shockwave from empty to full.
+pub const SHOCKWAVE_CHARGE_SECONDS: f32 = 30.0;
+/// Charge gained when an enemy passes close by without hitting.
+pub const NEAR_MISS_CHARGE: f32 = 0.05;
+/// How far outside the player an enemy counts as a near miss.
+const NEAR_MISS_MARGIN: f32 = 30.0;
+const SHOCKWAVE_EFFECT_SECONDS: f32 = 0.3;
+
+pub struct ShockwavePlugin;
+
+impl Plugin for ShockwavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                charge_shockwave,
+                count_near_misses,
+                fire_shockwave,
+                fade_shockwave_effects,
+            )
+                .chain()
+                .in_set(GameplaySet::Player),
+        )
+        .add_systems(OnEnter(State::Starting), remove_shockwave_effects)
+        .add_systems(OnEnter(State::MainMenu), remove_shockwave_effects);
+    }
+}
+
+#[derive(Component, Default)]
+/// Charges up while the player survives, and destroys the enemies around the player when
+/// fired at full charge.
+pub struct Shockwave {
+    /// From 0 to 1, the shockwave can be fired at 1.
+    charge: f32,
+    /// Whether the shockwave button was held last tick.
+    was_pressed: bool,
+}
+
+impl Shockwave {
+    pub fn charge(&self) -> f32 {
+        self.charge
+    }
+
+    pub fn is_charged(&self) -> bool {
+        self.charge >= 1.0
+    }
+
+    fn add_charge(&mut self, charge: f32) {
+        self.charge = (self.charge + charge).min(1.0);
+    }
+}
+
+#[derive(Component)]
+/// The enemy already charged the shockwave by passing close to the player.
+struct NearMissed;
+
+#[derive(Component)]
+/// The visible pulse of a fired shockwave.
+struct ShockwaveEffect(Timer);
+
+fn charge_shockwave(mut shockwaves: Query<&mut Shockwave>, time: Res<Time>) {
+    for mut shockwave in shockwaves.iter_mut() {
+        shockwave.add_charge(time.delta_secs() / SHOCKWAVE_CHARGE_SECONDS);
+    }
+}
+
+/// Enemies that can still count as a near miss. Unarmed mines are harmless, so passing them
+/// does not count.
+type NearMissCandidates = (Without<NearMissed>, Without<Unarmed>);
+
+/// Each enemy that comes within [NEAR_MISS_MARGIN] of the player charges the shockwave once.
+/// An enemy that goes on to hit the player is destroyed by the hit, so the charge is a small
+/// consolation at most.
+fn count_near_misses(
+    mut commands: Commands,
+    mut players: Query<(&Transform, &ShipStats, &PlayerInput, &mut Shockwave), With<Player>>,
+    enemies: Query<(Entity, &Transform, &Hitbox), NearMissCandidates>,
+) {
+    for (player_transform, stats, input, mut shockwave) in players.iter_mut() {
+        let near_aabb = stats
+            .hitbox(player_transform, input.focus)
+            .grow(Vec2::splat(NEAR_MISS_MARGIN));
+        for (enemy_entity, enemy_transform, hitbox) in enemies.iter() {
+            let enemy_aabb = Aabb2d::new(enemy_transform.translation.xy(), 0.5 * hitbox.0);
+            if near_aabb.intersects(&enemy_aabb) {
+                commands.entity(enemy_entity).insert(NearMissed);
+                shockwave.add_charge(NEAR_MISS_CHARGE);
+            }
+        }
+    }
+}
+
+fn fire_shockwave(
+    mut commands: Commands,
+    mut destroyed_events: EventWriter<EnemyDestroyed>,
+    mut players: Query<(&Player, &Transform, &PlayerInput, &mut Shockwave)>,
+    enemies: Query<(Entity, &Transform, &Enemy), Without<Boss>>,
+    mut bosses: Query<(&Transform, &mut Boss)>,
+) {
+    // Players firing on the same tick can not destroy the same enemy twice.
+    let mut destroyed = Vec::new();
+    for (player, player_transform, input, mut shockwave) in players.iter_mut() {
+        let pressed = input.shockwave && !shockwave.was_pressed;
+        shockwave.was_pressed = input.shockwave;
+        if !pressed || !shockwave.is_charged() {
+            continue;
+        }
+        shockwave.charge = 0.0;
+
+        let center = player_transform.translation;
+        for (enemy_entity, enemy_transform, enemy) in enemies.iter() {
+            if !destroyed.contains(&enemy_entity)
+                && enemy_transform.translation.distance(center) <= SHOCKWAVE_RADIUS
+            {
+                destroyed.push(enemy_entity);
+                commands.entity(enemy_entity).despawn();
+                destroyed_events.send(EnemyDestroyed {
+                    player: player.index,
+                    enemy: *enemy,
+                    position: enemy_transform.translation,
+                });
+            }
+        }
+        for (boss_transform, mut boss) in bosses.iter_mut() {
+            if boss_transform.translation.distance(center) <= SHOCKWAVE_RADIUS {
+                boss.hit();
+            }
+        }
+        commands.spawn((
+            Sprite::from_color(
+                Color::srgba(1.0, 1.0, 3.0, 0.5),
+                Vec2::splat(2.0 * SHOCKWAVE_RADIUS),
+            ),
+            Transform::from_translation(center.with_z(-1.0)),
+            ShockwaveEffect(Timer::from_seconds(
+                SHOCKWAVE_EFFECT_SECONDS,
+                TimerMode::Once,
+            )),
+        ));
+    }
+}
+
+/// Grows the pulse out to the radius while it fades.
+fn fade_shockwave_effects(
+    mut commands: Commands,
+    mut effects: Query<(Entity, &mut ShockwaveEffect, &mut Sprite, &mut Transform)>,
+    time: Res<Time>,
+) {
+    for (entity, mut effect, mut sprite, mut transform) in effects.iter_mut() {
+        if effect.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let progress = effect.0.fraction();
+        transform.scale = Vec3::splat(progress);
+        sprite.color.set_alpha(0.5 * (1.0 - progress));
+    }
+}
+
+fn remove_shockwave_effects(mut commands: Commands, effects: Query<Entity, With<ShockwaveEffect>>) {
+    for entity in effects.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,3 +1,7 @@
+pub mod assets;
 pub mod gameplay;
+pub mod sim;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod storage;
 pub mod ui;
 pub mod world;
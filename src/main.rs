@@ -1,27 +1,90 @@
-use bevy::core_pipeline::bloom::Bloom;
 use bevy::{prelude::*, render::camera::ScalingMode};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy_creeps::assets::DataAssets;
 use bevy_creeps::gameplay::GameplayPlugin;
+#[cfg(not(target_arch = "wasm32"))]
+use bevy_creeps::gameplay::enemies::WaveScriptPath;
+use bevy_creeps::gameplay::input::{Action, Actions};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy_creeps::gameplay::player::PlayerCount;
+#[cfg(not(target_arch = "wasm32"))]
+use bevy_creeps::gameplay::replay::{Replay, ReplayPlayback};
+use bevy_creeps::gameplay::rng::SeedConfig;
 use bevy_creeps::ui::UIPlugin;
 use bevy_creeps::world::{State, WORLD_SIZE};
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                fit_canvas_to_parent: true,
-                canvas: Some(String::from("#game")),
-                prevent_default_event_handling: false,
-                ..Default::default()
-            }),
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(WindowPlugin {
+        primary_window: Some(Window {
+            fit_canvas_to_parent: true,
+            canvas: Some(String::from("#game")),
+            prevent_default_event_handling: false,
             ..Default::default()
-        }))
-        .insert_resource(ClearColor(Color::BLACK))
-        .add_plugins(UIPlugin)
-        .add_plugins(GameplayPlugin)
-        .init_state::<State>()
-        .add_systems(Startup, setup_camera)
-        .add_systems(Update, check_restart.run_if(in_state(State::GameOver)))
-        .run();
+        }),
+        ..Default::default()
+    }))
+    .insert_resource(ClearColor(Color::BLACK))
+    .add_plugins(UIPlugin)
+    .add_plugins(GameplayPlugin)
+    .insert_resource(SeedConfig(
+        arg_value("--seed").and_then(|seed| seed.parse().ok()),
+    ))
+    .init_state::<State>()
+    .add_systems(Startup, setup_camera)
+    .add_systems(Update, check_restart.run_if(in_state(State::GameOver)));
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = arg_value("--replay") {
+        match Replay::load(std::path::Path::new(&path)) {
+            Ok(replay) => {
+                replay.mode.select(&mut app.world_mut().commands());
+                app.world_mut().flush();
+                app.insert_resource(SeedConfig(Some(replay.seed)))
+                    .insert_resource(PlayerCount(replay.players))
+                    .insert_resource(replay.ships)
+                    .insert_resource(WaveScriptPath(replay.wave_script.clone()))
+                    .insert_resource(ReplayPlayback::new(replay))
+                    .insert_resource(PendingReplay)
+                    .add_systems(
+                        Update,
+                        start_replay.run_if(resource_exists::<PendingReplay>),
+                    );
+            }
+            Err(error) => error!("Could not load replay {path}: {error}"),
+        }
+    }
+
+    app.run();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource)]
+/// A replay from the command line is waiting for the data files it was recorded with.
+struct PendingReplay;
+
+/// Starts the replay once the data files are in, so its first tick runs on the same data as
+/// the recorded run.
+#[cfg(not(target_arch = "wasm32"))]
+fn start_replay(
+    mut commands: Commands,
+    data_assets: Res<DataAssets>,
+    asset_server: Res<AssetServer>,
+    mut nextstate: ResMut<NextState<State>>,
+) {
+    if !data_assets.settled(&asset_server) {
+        return;
+    }
+    commands.remove_resource::<PendingReplay>();
+    match data_assets.load_error(&asset_server) {
+        Some(error) => error!("Could not play the replay without its data files: {error}"),
+        None => nextstate.set(State::Starting),
+    }
+}
+
+/// Reads the value following `name` on the command line, e.g. `--seed 42`.
+fn arg_value(name: &str) -> Option<String> {
+    std::env::args().skip_while(|arg| arg != name).nth(1)
 }
 
 fn setup_camera(mut commands: Commands) {
@@ -36,16 +99,12 @@ fn setup_camera(mut commands: Commands) {
             hdr: true,
             ..Default::default()
         },
-        Bloom::NATURAL,
         projection,
     ));
 }
 
-fn check_restart(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut nextstate: ResMut<NextState<State>>,
-) {
-    if keyboard_input.pressed(KeyCode::KeyR) {
-        nextstate.set(State::Playing)
+fn check_restart(actions: Actions, mut nextstate: ResMut<NextState<State>>) {
+    if actions.any_just_pressed(Action::Restart) {
+        nextstate.set(State::Starting)
     }
 }
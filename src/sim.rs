@@ -0,0 +1,303 @@
+use crate::assets::DataAssets;
+use crate::gameplay::GameplayPlugin;
+use crate::gameplay::enemies::{
+    Enemy, EnemyDefinitions, RandomSpawning, WaveScriptPath, spawn_single_enemy,
+};
+use crate::gameplay::high_scores::SaveHighScores;
+use crate::gameplay::input::PlayerInput;
+use crate::gameplay::mode::GameMode;
+use crate::gameplay::player::{CauseOfDeath, MAX_PLAYERS, Player, PlayerCount};
+use crate::gameplay::replay::{Replay, ReplayPlayback, SaveReplays};
+use crate::gameplay::rng::{RunSeed, SeedConfig};
+use crate::gameplay::score::Score;
+use crate::gameplay::ships::{Ship, ShipSelection};
+use crate::world::State;
+use bevy::asset::AssetLoadError;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GameSimError {
+    #[error("could not load the game data: {0}")]
+    DataAsset(Arc<AssetLoadError>),
+    #[error("a run has 1 to {MAX_PLAYERS} players, not {0}")]
+    PlayerCount(usize),
+}
+
+/// Builds a [GameSim].
+pub struct GameSimBuilder {
+    seed: Option<u64>,
+    mode: GameMode,
+    players: usize,
+    ships: ShipSelection,
+    random_spawning: bool,
+    wave_script: Option<WaveScriptPath>,
+    replay: Option<Replay>,
+}
+
+impl Default for GameSimBuilder {
+    fn default() -> Self {
+        GameSimBuilder {
+            seed: None,
+            mode: GameMode::default(),
+            players: 1,
+            ships: ShipSelection::default(),
+            random_spawning: true,
+            wave_script: None,
+            replay: None,
+        }
+    }
+}
+
+impl GameSimBuilder {
+    /// Seed for the run. Without one a random seed is drawn, see [GameSim::seed].
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn mode(mut self, mode: GameMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// How many players play the run, from 1 to [MAX_PLAYERS]. They all get the input set with
+    /// [GameSim::set_input].
+    pub fn players(mut self, players: usize) -> Self {
+        self.players = players;
+        self
+    }
+
+    /// The ship every player flies.
+    pub fn ship(mut self, ship: Ship) -> Self {
+        self.ships = ShipSelection([ship; MAX_PLAYERS]);
+        self
+    }
+
+    /// The ship of each player.
+    pub fn ships(mut self, ships: ShipSelection) -> Self {
+        self.ships = ships;
+        self
+    }
+
+    /// Turn the random enemy spawner and the wave script off to only have the enemies spawned
+    /// with [GameSim::spawn_enemy].
+    pub fn random_spawning(mut self, random_spawning: bool) -> Self {
+        self.random_spawning = random_spawning;
+        self
+    }
+
+    /// The wave script to play instead of the one of the mode, relative to `assets/`.
+    /// `None` plays no script.
+    pub fn wave_script(mut self, path: Option<&str>) -> Self {
+        self.wave_script = Some(WaveScriptPath(path.map(String::from)));
+        self
+    }
+
+    /// Plays `replay` back. Its seed, mode, players, ships and wave script replace the ones set
+    /// on the builder, and the players are driven by its inputs.
+    pub fn replay(mut self, replay: Replay) -> Self {
+        self.seed = Some(replay.seed);
+        self.mode = replay.mode;
+        self.players = replay.players;
+        self.ships = replay.ships;
+        self.wave_script = Some(WaveScriptPath(replay.wave_script.clone()));
+        self.replay = Some(replay);
+        self
+    }
+
+    /// Blocks until the data files in `assets/` have loaded, so every run plays with the same data
+    /// however long loading takes.
+    pub fn build(self) -> Result<GameSim, GameSimError> {
+        if !PlayerCount(self.players).is_valid() {
+            return Err(GameSimError::PlayerCount(self.players));
+        }
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            StatesPlugin,
+            GameplayPlugin,
+        ))
+        .insert_state(State::Starting)
+        .insert_resource(SeedConfig(self.seed))
+        .insert_resource(PlayerCount(self.players))
+        .insert_resource(self.ships)
+        .insert_resource(SaveReplays(false))
+        .insert_resource(SaveHighScores(false))
+        .insert_resource(RandomSpawning(self.random_spawning))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+        self.mode.select(&mut app.world_mut().commands());
+        app.world_mut().flush();
+        if !self.random_spawning {
+            app.insert_resource(WaveScriptPath(None));
+        }
+        if let Some(wave_script) = self.wave_script {
+            app.insert_resource(wave_script);
+        }
+        if let Some(replay) = self.replay {
+            app.insert_resource(ReplayPlayback::new(replay));
+        }
+        app.finish();
+        app.cleanup();
+
+        // Start the run without advancing time, so the first step is the first tick.
+        // Keep updating until the data files are in so every run starts from the same data.
+        app.update();
+        while !app
+            .world()
+            .resource::<DataAssets>()
+            .settled(app.world().resource::<AssetServer>())
+        {
+            std::thread::sleep(Duration::from_millis(1));
+            app.update();
+        }
+        if let Some(error) = app
+            .world()
+            .resource::<DataAssets>()
+            .load_error(app.world().resource::<AssetServer>())
+        {
+            return Err(GameSimError::DataAsset(error));
+        }
+        while **app.world().resource::<bevy::prelude::State<State>>() != State::Playing {
+            app.update();
+        }
+        let timestep = app.world().resource::<Time<Fixed>>().timestep();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+
+        Ok(GameSim { app })
+    }
+}
+
+/// The gameplay on a headless [App] that is stepped by hand, one [FixedUpdate] tick at a time.
+/// Meant for scenario tests and balancing runs.
+pub struct GameSim {
+    app: App,
+}
+
+impl GameSim {
+    pub fn builder() -> GameSimBuilder {
+        GameSimBuilder::default()
+    }
+
+    /// Runs `ticks` [FixedUpdate] ticks.
+    pub fn step(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.app.update();
+        }
+    }
+
+    /// Runs as many ticks as fit in `seconds` of game time.
+    pub fn step_seconds(&mut self, seconds: f32) {
+        let timestep = self.app.world().resource::<Time<Fixed>>().timestep();
+        self.step((seconds / timestep.as_secs_f32()).round() as u32);
+    }
+
+    pub fn spawn_enemy(&mut self, enemy: Enemy, position: Vec2, direction: Vec2) {
+        let world = self.app.world_mut();
+        let definitions = world.resource::<EnemyDefinitions>().clone();
+        spawn_single_enemy(
+            enemy,
+            position.extend(0.0),
+            direction.normalize_or_zero().extend(0.0),
+            &definitions,
+            &mut world.commands(),
+        );
+        world.flush();
+    }
+
+    /// Sets the movement input every player holds until it is set again.
+    pub fn set_input(&mut self, movement: Vec2) {
+        self.for_each_input(|input| input.movement = movement);
+    }
+
+    /// Holds the dash button down, or lets go of it. A dash starts when it goes down.
+    pub fn set_dash(&mut self, dash: bool) {
+        self.for_each_input(|input| input.dash = dash);
+    }
+
+    /// Holds the shockwave button down, or lets go of it. The shockwave fires when it goes down.
+    pub fn set_shockwave(&mut self, shockwave: bool) {
+        self.for_each_input(|input| input.shockwave = shockwave);
+    }
+
+    /// Holds the focus button down, or lets go of it.
+    pub fn set_focus(&mut self, focus: bool) {
+        self.for_each_input(|input| input.focus = focus);
+    }
+
+    fn for_each_input(&mut self, f: impl Fn(&mut PlayerInput)) {
+        let world = self.app.world_mut();
+        let mut players = world.query_filtered::<&mut PlayerInput, With<Player>>();
+        for mut input in players.iter_mut(world) {
+            f(&mut input);
+        }
+    }
+
+    /// Position of the first player that is still in, `None` once every player is out.
+    pub fn player_position(&mut self) -> Option<Vec2> {
+        let world = self.app.world_mut();
+        let mut players = world.query::<(&Player, &Transform)>();
+        players
+            .iter(world)
+            .min_by_key(|(player, _)| player.index)
+            .map(|(_, transform)| transform.translation.xy())
+    }
+
+    /// Positions of the players that are still in, by [Player::index].
+    pub fn player_positions(&mut self) -> Vec<Vec2> {
+        let world = self.app.world_mut();
+        let mut players: Vec<_> = world
+            .query::<(&Player, &Transform)>()
+            .iter(world)
+            .map(|(player, transform)| (player.index, transform.translation.xy()))
+            .collect();
+        players.sort_by_key(|(index, _)| *index);
+        players.into_iter().map(|(_, position)| position).collect()
+    }
+
+    /// Every enemy with its position, in the same order on every run with the same seed and input.
+    pub fn enemies(&mut self) -> Vec<(Enemy, Vec2)> {
+        let world = self.app.world_mut();
+        let mut enemies: Vec<_> = world
+            .query::<(Entity, &Enemy, &Transform)>()
+            .iter(world)
+            .map(|(entity, enemy, transform)| (entity, *enemy, transform.translation.xy()))
+            .collect();
+        enemies.sort_by_key(|(entity, ..)| *entity);
+        enemies
+            .into_iter()
+            .map(|(_, enemy, position)| (enemy, position))
+            .collect()
+    }
+
+    pub fn state(&self) -> State {
+        **self.app.world().resource::<bevy::prelude::State<State>>()
+    }
+
+    pub fn mode(&self) -> GameMode {
+        *self.app.world().resource::<GameMode>()
+    }
+
+    pub fn score(&self) -> u32 {
+        self.app.world().resource::<Score>().0
+    }
+
+    pub fn cause_of_death(&self) -> Option<Enemy> {
+        self.app.world().resource::<CauseOfDeath>().0
+    }
+
+    /// The seed the run was started with.
+    pub fn seed(&self) -> u64 {
+        self.app.world().resource::<RunSeed>().0
+    }
+
+    /// Access to the underlying [App], e.g. to add systems that drive the player.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
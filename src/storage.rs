@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+/// Directory the game writes its files to, such as replays.
+/// Only available on native builds, the web build has no filesystem.
+pub fn data_dir() -> PathBuf {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .unwrap_or_default()
+        .join("bevy-creeps")
+}
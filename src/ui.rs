@@ -1,56 +1,145 @@
-use crate::gameplay::score::Score;
+use crate::gameplay::GameplaySet;
+use crate::gameplay::enemies::Boss;
+use crate::gameplay::high_scores::{
+    HIGH_SCORE_TABLE_SIZE, HighScoreEntry, HighScores, record_high_score,
+};
+use crate::gameplay::input::{Action, Binding, InputBindings};
+use crate::gameplay::mode::GameMode;
+use crate::gameplay::player::{Dash, Health, Player, PlayerCount};
+use crate::gameplay::power_ups::{Shield, SlowTime};
+use crate::gameplay::score::{PlayerScores, Score};
+use crate::gameplay::shockwave::Shockwave;
 use crate::world::State;
 use bevy::prelude::*;
+use menu::{Menu, MenuAction};
+
+mod controls;
+mod main_menu;
+mod menu;
+mod pause;
+mod settings;
+
+pub use main_menu::MenuPage;
+pub use settings::Settings;
 
 pub struct UIPlugin;
 
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_plugins((
+            controls::ControlsPlugin,
+            main_menu::MainMenuPlugin,
+            menu::MenuPlugin,
+            pause::PausePlugin,
+            settings::SettingsPlugin,
+        ))
+        .add_systems(
             FixedUpdate,
-            (update_score_ui).run_if(in_state(State::Playing)),
+            (
+                update_score_ui,
+                update_player_ui,
+                update_power_up_ui,
+                update_boss_ui,
+            )
+                .after(GameplaySet::Progress)
+                .run_if(in_state(State::Playing)),
         )
         .add_systems(
-            OnEnter(State::Playing),
-            (remove_game_over_ui, create_score_ui),
+            OnEnter(State::Starting),
+            (remove_score_ui, create_score_ui).chain(),
         )
+        .add_systems(OnExit(State::GameOver), remove_game_over_ui)
+        .add_systems(OnEnter(State::MainMenu), remove_score_ui)
         .add_systems(
             OnEnter(State::GameOver),
-            (create_game_over_ui, remove_score_ui),
+            (
+                create_game_over_ui.after(record_high_score),
+                remove_score_ui,
+            ),
         );
     }
 }
+const BOSS_HEALTH_BAR_WIDTH: f32 = 300.0;
+
 #[derive(Component)]
 struct GameOverUI;
 
-fn create_game_over_ui(mut commands: Commands, score: Res<Score>) {
-    commands
-        .spawn((
-            GameOverUI,
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                justify_content: JustifyContent::Center,
-                align_content: AlignContent::Center,
-                align_items: AlignItems::Center,
-                flex_direction: FlexDirection::Column,
-                display: Display::Flex,
-                ..Default::default()
-            },
-        ))
-        .with_children(|parent| {
-            parent
-                .spawn(Node::default())
-                .with_child(Text::new("Game Over"));
+fn create_game_over_ui(
+    mut commands: Commands,
+    score: Res<Score>,
+    player_scores: Res<PlayerScores>,
+    high_scores: Res<HighScores>,
+    mode: Res<GameMode>,
+    bindings: Res<InputBindings>,
+) {
+    let menu = Menu::new("Game Over").line(format!("Score: {}", score.0));
+    let menu = if player_scores.0.len() > 1 {
+        player_scores
+            .0
+            .iter()
+            .enumerate()
+            .fold(menu, |menu, (index, player_score)| {
+                menu.line(format!("P{}  {player_score}", index + 1))
+            })
+    } else {
+        menu
+    };
+    let menu = match high_scores.latest(*mode) {
+        Some(0) => menu.highlighted_line("New high score!"),
+        Some(_) => menu.highlighted_line(format!("Top {HIGH_SCORE_TABLE_SIZE}!")),
+        None => menu,
+    };
+    high_score_table(menu, &high_scores, *mode)
+        .line(restart_hint(&bindings))
+        .button("Restart", MenuAction::StartRun)
+        .button("Main menu", MenuAction::MainMenu)
+        .spawn(&mut commands, GameOverUI);
+}
 
-            parent
-                .spawn(Node::default())
-                .with_child(Text::new("Press <R> to restart"));
+fn restart_hint(bindings: &InputBindings) -> String {
+    let bound: Vec<String> = bindings
+        .get(0, Action::Restart)
+        .iter()
+        .map(Binding::name)
+        .collect();
+    if bound.is_empty() {
+        return String::new();
+    }
+    format!("Press {} to restart", bound.join(" or "))
+}
 
-            parent
-                .spawn(Node::default())
-                .with_child(Text::new(format!("Score: {}", score.0)));
-        });
+/// Adds a line per [HighScoreEntry] of `mode`, highlighting the one the last run earned.
+fn high_score_table(menu: Menu, high_scores: &HighScores, mode: GameMode) -> Menu {
+    let entries = high_scores.entries(mode);
+    if entries.is_empty() {
+        return menu.line(format!("No {} high scores yet", mode.name()));
+    }
+    entries
+        .iter()
+        .enumerate()
+        .fold(menu, |menu, (position, entry)| {
+            let line = high_score_line(position, entry);
+            if high_scores.latest(mode) == Some(position) {
+                menu.highlighted_line(line)
+            } else {
+                menu.line(line)
+            }
+        })
+}
+
+fn high_score_line(position: usize, entry: &HighScoreEntry) -> String {
+    let cause = match entry.cause {
+        Some(enemy) => format!("{enemy:?}"),
+        None => String::from("-"),
+    };
+    format!(
+        "{:>2}. {:>5}  {}  {}  seed {}",
+        position + 1,
+        entry.score,
+        entry.date_string(),
+        cause,
+        entry.seed
+    )
 }
 
 fn remove_game_over_ui(
@@ -68,7 +157,24 @@ struct ScoreUI;
 #[derive(Component)]
 struct ScoreText;
 
-fn create_score_ui(mut commands: Commands) {
+#[derive(Component)]
+/// The status line of the player with this [Player::index].
+struct PlayerText(usize);
+
+#[derive(Component)]
+struct PowerUpText;
+
+#[derive(Component)]
+/// Only shown while a [Boss] is alive.
+struct BossUI;
+
+#[derive(Component)]
+struct BossText;
+
+#[derive(Component)]
+struct BossHealthBar;
+
+fn create_score_ui(mut commands: Commands, player_count: Res<PlayerCount>) {
     commands
         .spawn((
             ScoreUI,
@@ -78,18 +184,135 @@ fn create_score_ui(mut commands: Commands) {
                 left: Val::Px(10.0),
                 align_items: AlignItems::Start,
                 justify_content: JustifyContent::Start,
+                flex_direction: FlexDirection::Column,
                 ..Default::default()
             },
         ))
         .with_children(|parent| {
-            parent.spawn(Text::new("Score: "));
-            parent.spawn((ScoreText, Text::new("0")));
+            parent.spawn(Node::default()).with_children(|parent| {
+                parent.spawn(Text::new("Score: "));
+                parent.spawn((ScoreText, Text::new("0")));
+                parent.spawn((PowerUpText, Text::new("")));
+            });
+            for index in 0..player_count.0 {
+                parent.spawn((
+                    PlayerText(index),
+                    Text::new(""),
+                    TextColor(Player { index }.color()),
+                ));
+            }
+            parent
+                .spawn((
+                    BossUI,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        margin: UiRect::top(Val::Px(10.0)),
+                        ..Default::default()
+                    },
+                    Visibility::Hidden,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((BossText, Text::new("")));
+                    parent
+                        .spawn((
+                            Node {
+                                width: Val::Px(BOSS_HEALTH_BAR_WIDTH),
+                                height: Val::Px(12.0),
+                                ..Default::default()
+                            },
+                            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                        ))
+                        .with_child((
+                            BossHealthBar,
+                            Node {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..Default::default()
+                            },
+                            BackgroundColor(Color::srgb(0.9, 0.1, 0.1)),
+                        ));
+                });
         });
 }
 fn update_score_ui(mut score_text: Single<&mut Text, With<ScoreText>>, score: Res<Score>) {
     score_text.0 = format!("{}", score.0);
 }
 
+/// What a player's status line shows.
+type PlayerStatus = (
+    &'static Player,
+    &'static Health,
+    &'static Dash,
+    &'static Shockwave,
+    Has<Shield>,
+);
+
+/// Shows lives, dash, shockwave and shield of every player. In co-op each line starts with
+/// the score of that player, and players that are out only show their score.
+fn update_player_ui(
+    mut player_texts: Query<(&PlayerText, &mut Text)>,
+    players: Query<PlayerStatus>,
+    player_scores: Res<PlayerScores>,
+    player_count: Res<PlayerCount>,
+) {
+    for (player_text, mut text) in player_texts.iter_mut() {
+        let index = player_text.0;
+        let mut parts = Vec::new();
+        if player_count.0 > 1 {
+            let player_score = player_scores.0.get(index).copied().unwrap_or_default();
+            parts.push(format!("P{}  Score: {player_score}", index + 1));
+        }
+        match players.iter().find(|(player, ..)| player.index == index) {
+            Some((_, health, dash, shockwave, shielded)) => {
+                parts.push(format!("Lives: {}", health.hits_left));
+                let remaining = dash.cooldown_remaining();
+                parts.push(if remaining > 0.0 {
+                    format!("Dash: {remaining:.1}s")
+                } else {
+                    String::from("Dash: ready")
+                });
+                parts.push(if shockwave.is_charged() {
+                    String::from("Shockwave: ready")
+                } else {
+                    format!("Shockwave: {:.0}%", 100.0 * shockwave.charge())
+                });
+                if shielded {
+                    parts.push(String::from("Shield"));
+                }
+            }
+            None => parts.push(String::from("Out")),
+        }
+        text.0 = parts.join("   ");
+    }
+}
+
+/// Shows the power-ups that affect every player, with the time they have left.
+fn update_power_up_ui(
+    mut power_up_text: Single<&mut Text, With<PowerUpText>>,
+    slow_time: Res<SlowTime>,
+) {
+    power_up_text.0 = match slow_time.remaining_secs() {
+        Some(remaining) => format!("   Slow time {remaining:.1}s"),
+        None => String::new(),
+    };
+}
+
+/// Shows the health of the boss and how long it has left, while there is one.
+fn update_boss_ui(
+    mut boss_ui: Single<&mut Visibility, With<BossUI>>,
+    mut boss_text: Single<&mut Text, With<BossText>>,
+    mut health_bar: Single<&mut Node, With<BossHealthBar>>,
+    bosses: Query<&Boss>,
+) {
+    let Some(boss) = bosses.iter().next() else {
+        **boss_ui = Visibility::Hidden;
+        return;
+    };
+    **boss_ui = Visibility::Inherited;
+    boss_text.0 = format!("Boss   {:.0}s", boss.remaining_secs().ceil());
+    health_bar.width = Val::Percent(100.0 * boss.health_fraction());
+}
+
 fn remove_score_ui(score_entity: Option<Single<Entity, With<ScoreUI>>>, mut commands: Commands) {
     if let Some(entity) = score_entity {
         commands.entity(*entity).despawn_recursive();
@@ -0,0 +1,136 @@
+use super::main_menu::{MainMenuUI, MenuPage, remove_main_menu_ui};
+use super::menu::{Menu, MenuAction, MenuSet};
+use crate::gameplay::input::{Action, Binding, InputBindings};
+use crate::gameplay::player::MAX_PLAYERS;
+use bevy::prelude::*;
+
+/// The controls page of the main menu, where actions are bound to keys and buttons.
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>()
+            .add_systems(OnEnter(MenuPage::Controls), create_controls_page)
+            .add_systems(OnExit(MenuPage::Controls), stop_rebinding)
+            .add_systems(
+                Update,
+                (
+                    // The menu skips input while an action waits, and the press that starts
+                    // rebinding must not be taken as the new binding.
+                    capture_binding
+                        .after(MenuSet::Navigate)
+                        .before(MenuSet::Act)
+                        .run_if(|rebinding: Res<Rebinding>| rebinding.action.is_some()),
+                    (remove_main_menu_ui, refresh_controls_page).chain().run_if(
+                        in_state(MenuPage::Controls).and(
+                            resource_changed::<InputBindings>.or(resource_changed::<Rebinding>),
+                        ),
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+/// Whose bindings the controls page shows, and the action waiting for a key or button press.
+/// The menu ignores input while an action waits.
+pub struct Rebinding {
+    pub player: usize,
+    pub action: Option<Action>,
+}
+
+impl Rebinding {
+    /// Shows the bindings of the next player, going round to the first after the last.
+    pub fn next_player(&mut self) {
+        self.player = (self.player + 1) % MAX_PLAYERS;
+    }
+}
+
+fn controls_menu(bindings: &InputBindings, rebinding: &Rebinding) -> Menu {
+    let menu = Menu::new("Controls");
+    let menu = match rebinding.action {
+        Some(action) => menu.highlighted_line(format!(
+            "Press a key or button to bind or unbind it for {}, Escape to cancel",
+            action.name()
+        )),
+        None => menu.line("Pick an action to bind or unbind a key or button"),
+    };
+    let menu = menu.button(
+        format!("Player {}", rebinding.player + 1),
+        MenuAction::NextControlsPlayer,
+    );
+    Action::ALL
+        .iter()
+        .fold(menu, |menu, action| {
+            let bound: Vec<String> = bindings
+                .get(rebinding.player, *action)
+                .iter()
+                .map(Binding::name)
+                .collect();
+            menu.button(
+                format!("{}: {}", action.name(), bound.join(", ")),
+                MenuAction::Rebind(*action),
+            )
+        })
+        .button("Reset to defaults", MenuAction::ResetBindings)
+        .back_button("Back", MenuAction::OpenPage(MenuPage::Settings))
+}
+
+fn create_controls_page(
+    mut commands: Commands,
+    bindings: Res<InputBindings>,
+    rebinding: Res<Rebinding>,
+) {
+    controls_menu(&bindings, &rebinding).spawn(&mut commands, MainMenuUI);
+}
+
+fn refresh_controls_page(
+    mut commands: Commands,
+    bindings: Res<InputBindings>,
+    rebinding: Res<Rebinding>,
+) {
+    controls_menu(&bindings, &rebinding)
+        .keep_focus()
+        .spawn(&mut commands, MainMenuUI);
+}
+
+fn stop_rebinding(mut rebinding: ResMut<Rebinding>) {
+    rebinding.action = None;
+}
+
+/// Takes the next key or gamepad button that goes down for the action being rebound.
+/// Escape cancels instead, so it can not be bound.
+fn capture_binding(
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+) {
+    let Some(action) = rebinding.action else {
+        return;
+    };
+    let pressed = keyboard_input
+        .get_just_pressed()
+        .next()
+        .map(|key| Binding::Key(*key))
+        .or_else(|| {
+            gamepads.iter().find_map(|gamepad| {
+                gamepad
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| Binding::GamepadButton(*button))
+            })
+        });
+    let Some(binding) = pressed else {
+        return;
+    };
+    if binding == Binding::Key(KeyCode::Escape) {
+        rebinding.action = None;
+        return;
+    }
+
+    bindings.toggle(rebinding.player, action, binding);
+    bindings.persist();
+    rebinding.action = None;
+}
@@ -0,0 +1,186 @@
+use super::high_score_table;
+use super::menu::{Menu, MenuAction};
+use super::settings::Settings;
+use crate::gameplay::high_scores::HighScores;
+use crate::gameplay::mode::GameMode;
+use crate::gameplay::player::{MAX_PLAYERS, PlayerCount};
+use crate::gameplay::ships::{Ship, ShipSelection};
+use crate::world::State;
+use bevy::prelude::*;
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<MenuPage>()
+            .add_systems(OnEnter(MenuPage::Title), create_title_page)
+            .add_systems(OnEnter(MenuPage::Modes), create_modes_page)
+            .add_systems(OnEnter(MenuPage::Ships), create_ships_page)
+            .add_systems(OnEnter(MenuPage::Settings), create_settings_page)
+            .add_systems(OnEnter(MenuPage::HighScores), create_high_scores_page)
+            .add_systems(
+                Update,
+                (
+                    (remove_main_menu_ui, refresh_title_page)
+                        .chain()
+                        .run_if(in_state(MenuPage::Title).and(resource_changed::<PlayerCount>)),
+                    (remove_main_menu_ui, refresh_ships_page)
+                        .chain()
+                        .run_if(in_state(MenuPage::Ships).and(resource_changed::<ShipSelection>)),
+                    (remove_main_menu_ui, refresh_settings_page)
+                        .chain()
+                        .run_if(in_state(MenuPage::Settings).and(resource_changed::<Settings>)),
+                ),
+            );
+        for page in [
+            MenuPage::Title,
+            MenuPage::Modes,
+            MenuPage::Ships,
+            MenuPage::Settings,
+            MenuPage::Controls,
+            MenuPage::HighScores,
+        ] {
+            app.add_systems(OnExit(page), remove_main_menu_ui);
+        }
+    }
+}
+
+#[derive(SubStates, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[source(State = State::MainMenu)]
+pub enum MenuPage {
+    #[default]
+    Title,
+    Modes,
+    /// Picks the ship of every player before the run starts.
+    Ships,
+    Settings,
+    Controls,
+    HighScores,
+}
+
+#[derive(Component)]
+pub(super) struct MainMenuUI;
+
+fn title_menu(mode: GameMode, player_count: PlayerCount) -> Menu {
+    let menu = Menu::new("Bevy Creeps")
+        .button("Play", MenuAction::OpenPage(MenuPage::Ships))
+        .button(
+            format!("Mode: {}", mode.name()),
+            MenuAction::OpenPage(MenuPage::Modes),
+        )
+        .button(
+            format!("Players: {}", player_count.0),
+            MenuAction::SetPlayerCount(player_count.0 % MAX_PLAYERS + 1),
+        )
+        .button("Settings", MenuAction::OpenPage(MenuPage::Settings))
+        .button("High Scores", MenuAction::OpenPage(MenuPage::HighScores));
+    #[cfg(not(target_arch = "wasm32"))]
+    let menu = menu.button("Quit", MenuAction::Quit);
+    menu
+}
+
+fn create_title_page(mut commands: Commands, mode: Res<GameMode>, player_count: Res<PlayerCount>) {
+    title_menu(*mode, *player_count).spawn(&mut commands, MainMenuUI);
+}
+
+fn refresh_title_page(mut commands: Commands, mode: Res<GameMode>, player_count: Res<PlayerCount>) {
+    title_menu(*mode, *player_count)
+        .keep_focus()
+        .spawn(&mut commands, MainMenuUI);
+}
+
+fn create_modes_page(mut commands: Commands) {
+    GameMode::ALL
+        .iter()
+        .fold(Menu::new("Mode"), |menu, mode| {
+            menu.button(mode.name(), MenuAction::SelectMode(*mode))
+        })
+        .back_button("Back", MenuAction::OpenPage(MenuPage::Title))
+        .spawn(&mut commands, MainMenuUI);
+}
+
+fn ships_menu(ships: &ShipSelection, player_count: PlayerCount) -> Menu {
+    let menu = Ship::ALL.iter().fold(Menu::new("Ships"), |menu, ship| {
+        menu.line(format!("{}: {}", ship.name(), ship.description()))
+    });
+    (0..player_count.0)
+        .fold(menu, |menu, player| {
+            let ship = ships.0[player];
+            menu.button(
+                format!("Player {}: {}", player + 1, ship.name()),
+                MenuAction::SelectShip {
+                    player,
+                    ship: ship.next(),
+                },
+            )
+        })
+        .button("Start", MenuAction::StartRun)
+        .back_button("Back", MenuAction::OpenPage(MenuPage::Title))
+}
+
+fn create_ships_page(
+    mut commands: Commands,
+    ships: Res<ShipSelection>,
+    player_count: Res<PlayerCount>,
+) {
+    ships_menu(&ships, *player_count).spawn(&mut commands, MainMenuUI);
+}
+
+fn refresh_ships_page(
+    mut commands: Commands,
+    ships: Res<ShipSelection>,
+    player_count: Res<PlayerCount>,
+) {
+    ships_menu(&ships, *player_count)
+        .keep_focus()
+        .spawn(&mut commands, MainMenuUI);
+}
+
+fn settings_menu(settings: &Settings) -> Menu {
+    let on_off = |on| if on { "On" } else { "Off" };
+    Menu::new("Settings")
+        .button(
+            format!("Bloom: {}", on_off(settings.bloom)),
+            MenuAction::ToggleBloom,
+        )
+        .button(
+            format!("Stick deadzone: {:.0}%", 100.0 * settings.stick_deadzone),
+            MenuAction::NextStickDeadzone,
+        )
+        .button("Controls", MenuAction::OpenPage(MenuPage::Controls))
+        .back_button("Back", MenuAction::OpenPage(MenuPage::Title))
+}
+
+fn create_settings_page(mut commands: Commands, settings: Res<Settings>) {
+    settings_menu(&settings).spawn(&mut commands, MainMenuUI);
+}
+
+fn refresh_settings_page(mut commands: Commands, settings: Res<Settings>) {
+    settings_menu(&settings)
+        .keep_focus()
+        .spawn(&mut commands, MainMenuUI);
+}
+
+/// Shows the table of the selected [GameMode].
+fn create_high_scores_page(
+    mut commands: Commands,
+    high_scores: Res<HighScores>,
+    mode: Res<GameMode>,
+) {
+    high_score_table(
+        Menu::new(format!("High Scores: {}", mode.name())),
+        &high_scores,
+        *mode,
+    )
+    .back_button("Back", MenuAction::OpenPage(MenuPage::Title))
+    .spawn(&mut commands, MainMenuUI);
+}
+
+pub(super) fn remove_main_menu_ui(
+    mut commands: Commands,
+    main_menu_ui_entity: Option<Single<Entity, With<MainMenuUI>>>,
+) {
+    if let Some(entity) = main_menu_ui_entity {
+        commands.entity(*entity).despawn_recursive();
+    }
+}
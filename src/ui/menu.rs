@@ -0,0 +1,299 @@
+use super::controls::Rebinding;
+use super::main_menu::MenuPage;
+use super::settings::Settings;
+use crate::gameplay::input::{Action, InputBindings};
+use crate::gameplay::mode::GameMode;
+use crate::gameplay::player::PlayerCount;
+use crate::gameplay::ships::{Ship, ShipSelection};
+use crate::world::State;
+use bevy::prelude::*;
+
+const BUTTON_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const FOCUSED_BUTTON_COLOR: Color = Color::srgb(0.35, 0.35, 0.6);
+const HIGHLIGHTED_LINE_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+
+/// Lets the player pick a [MenuButton] with the keyboard, a gamepad or the mouse
+/// and carries out its [MenuAction].
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuFocus>()
+            .add_event::<MenuActionEvent>()
+            .add_systems(
+                Update,
+                (
+                    navigate_menu
+                        .in_set(MenuSet::Navigate)
+                        .run_if(|rebinding: Res<Rebinding>| rebinding.action.is_none()),
+                    highlight_focused_button,
+                    handle_menu_actions.in_set(MenuSet::Act),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(super) enum MenuSet {
+    /// Turns input into [MenuActionEvent]s.
+    Navigate,
+    /// Carries out the [MenuActionEvent]s.
+    Act,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuAction {
+    /// Starts a new run, also used to restart.
+    StartRun,
+    Resume,
+    MainMenu,
+    OpenPage(MenuPage),
+    SelectMode(GameMode),
+    SetPlayerCount(usize),
+    SelectShip {
+        player: usize,
+        ship: Ship,
+    },
+    ToggleBloom,
+    /// Moves on to the next stick deadzone, see [Settings::next_stick_deadzone].
+    NextStickDeadzone,
+    /// Waits for a key or button to bind to the action.
+    Rebind(Action),
+    /// Shows the bindings of the next player on the controls page.
+    NextControlsPlayer,
+    ResetBindings,
+    Quit,
+}
+
+#[derive(Event)]
+pub struct MenuActionEvent(pub MenuAction);
+
+#[derive(Component)]
+struct MenuButton {
+    index: usize,
+    action: MenuAction,
+}
+
+#[derive(Component)]
+/// Picked by Escape or the East button, to go back out of a menu.
+struct MenuBack(MenuAction);
+
+#[derive(Resource, Default)]
+/// Index of the [MenuButton] that is picked on confirm.
+struct MenuFocus(usize);
+
+/// A full screen menu: a title, some lines of text and a column of buttons.
+/// Only one menu should be on screen at a time.
+pub struct Menu {
+    title: String,
+    /// Text and whether it is highlighted.
+    lines: Vec<(String, bool)>,
+    buttons: Vec<(String, MenuAction)>,
+    back: Option<MenuAction>,
+    keep_focus: bool,
+}
+
+impl Menu {
+    pub fn new(title: impl Into<String>) -> Self {
+        Menu {
+            title: title.into(),
+            lines: Vec::new(),
+            buttons: Vec::new(),
+            back: None,
+            keep_focus: false,
+        }
+    }
+
+    pub fn line(mut self, line: impl Into<String>) -> Self {
+        self.lines.push((line.into(), false));
+        self
+    }
+
+    /// A line that stands out from the others.
+    pub fn highlighted_line(mut self, line: impl Into<String>) -> Self {
+        self.lines.push((line.into(), true));
+        self
+    }
+
+    pub fn button(mut self, label: impl Into<String>, action: MenuAction) -> Self {
+        self.buttons.push((label.into(), action));
+        self
+    }
+
+    /// The button that goes back out of the menu, also picked by Escape or the East button.
+    pub fn back_button(mut self, label: impl Into<String>, action: MenuAction) -> Self {
+        self.back = Some(action);
+        self.button(label, action)
+    }
+
+    /// Keeps the focus on the same button, for a menu that replaces an updated copy of itself.
+    pub fn keep_focus(mut self) -> Self {
+        self.keep_focus = true;
+        self
+    }
+
+    /// Spawns the menu with `root` on its top node, so the screen can find it to remove it.
+    pub fn spawn(self, commands: &mut Commands, root: impl Bundle) {
+        if !self.keep_focus {
+            commands.insert_resource(MenuFocus(0));
+        }
+        let mut menu = commands.spawn((
+            root,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(10.0),
+                ..Default::default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        ));
+        if let Some(back) = self.back {
+            menu.insert(MenuBack(back));
+        }
+        menu.with_children(|parent| {
+            parent.spawn(Text::new(self.title));
+            for (line, highlighted) in self.lines {
+                let color = if highlighted {
+                    HIGHLIGHTED_LINE_COLOR
+                } else {
+                    Color::WHITE
+                };
+                parent.spawn((Text::new(line), TextColor(color)));
+            }
+            for (index, (label, action)) in self.buttons.into_iter().enumerate() {
+                parent
+                    .spawn((
+                        Button,
+                        MenuButton { index, action },
+                        Node {
+                            width: Val::Px(240.0),
+                            padding: UiRect::all(Val::Px(8.0)),
+                            justify_content: JustifyContent::Center,
+                            ..Default::default()
+                        },
+                        BackgroundColor(BUTTON_COLOR),
+                    ))
+                    .with_child(Text::new(label));
+            }
+        });
+    }
+}
+
+fn navigate_menu(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    buttons: Query<(&MenuButton, Ref<Interaction>)>,
+    back: Option<Single<&MenuBack>>,
+    mut focus: ResMut<MenuFocus>,
+    mut actions: EventWriter<MenuActionEvent>,
+) {
+    let count = buttons.iter().count();
+    if count == 0 {
+        return;
+    }
+    let gamepad_pressed = |button| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+
+    if let Some(back) = back
+        && (keyboard_input.just_pressed(KeyCode::Escape) || gamepad_pressed(GamepadButton::East))
+    {
+        actions.send(MenuActionEvent(back.0));
+        return;
+    }
+
+    if keyboard_input.any_just_pressed([KeyCode::ArrowUp, KeyCode::KeyW])
+        || gamepad_pressed(GamepadButton::DPadUp)
+    {
+        focus.0 = (focus.0 + count - 1) % count;
+    }
+    if keyboard_input.any_just_pressed([KeyCode::ArrowDown, KeyCode::KeyS])
+        || gamepad_pressed(GamepadButton::DPadDown)
+    {
+        focus.0 = (focus.0 + 1) % count;
+    }
+
+    for (button, interaction) in buttons.iter() {
+        if !interaction.is_changed() {
+            continue;
+        }
+        match *interaction {
+            Interaction::Pressed => {
+                focus.0 = button.index;
+                actions.send(MenuActionEvent(button.action));
+                return;
+            }
+            Interaction::Hovered => focus.0 = button.index,
+            Interaction::None => {}
+        }
+    }
+
+    let confirmed = keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::Space])
+        || gamepad_pressed(GamepadButton::South);
+    if confirmed
+        && let Some((button, _)) = buttons.iter().find(|(button, _)| button.index == focus.0)
+    {
+        actions.send(MenuActionEvent(button.action));
+    }
+}
+
+fn highlight_focused_button(
+    focus: Res<MenuFocus>,
+    mut buttons: Query<(&MenuButton, &mut BackgroundColor)>,
+) {
+    for (button, mut color) in buttons.iter_mut() {
+        color.0 = if button.index == focus.0 {
+            FOCUSED_BUTTON_COLOR
+        } else {
+            BUTTON_COLOR
+        };
+    }
+}
+
+/// Every screen shares this handler, so an action is carried out exactly once
+/// no matter which state the game moves to afterwards.
+fn handle_menu_actions(
+    mut commands: Commands,
+    mut actions: EventReader<MenuActionEvent>,
+    mut nextstate: ResMut<NextState<State>>,
+    mut next_page: ResMut<NextState<MenuPage>>,
+    mut settings: ResMut<Settings>,
+    mut bindings: ResMut<InputBindings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for MenuActionEvent(action) in actions.read() {
+        match *action {
+            MenuAction::StartRun => nextstate.set(State::Starting),
+            MenuAction::Resume => nextstate.set(State::Playing),
+            MenuAction::MainMenu => nextstate.set(State::MainMenu),
+            MenuAction::OpenPage(page) => next_page.set(page),
+            MenuAction::SelectMode(mode) => {
+                mode.select(&mut commands);
+                next_page.set(MenuPage::Title);
+            }
+            MenuAction::ToggleBloom => {
+                settings.bloom = !settings.bloom;
+                settings.persist();
+            }
+            MenuAction::NextStickDeadzone => {
+                settings.next_stick_deadzone();
+                settings.persist();
+            }
+            MenuAction::SetPlayerCount(count) => commands.insert_resource(PlayerCount(count)),
+            MenuAction::SelectShip { player, ship } => {
+                ShipSelection::select(player, ship, &mut commands)
+            }
+            MenuAction::Rebind(action) => rebinding.action = Some(action),
+            MenuAction::NextControlsPlayer => rebinding.next_player(),
+            MenuAction::ResetBindings => {
+                *bindings = InputBindings::default();
+                bindings.persist();
+            }
+            MenuAction::Quit => {
+                commands.send_event(AppExit::Success);
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use super::menu::{Menu, MenuAction};
+use crate::gameplay::input::{Action, Actions};
+use crate::world::State;
+use bevy::prelude::*;
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                pause.run_if(in_state(State::Playing)),
+                resume.run_if(in_state(State::Paused)),
+            )
+                .run_if(pause_pressed),
+        )
+        .add_systems(OnEnter(State::Paused), (create_pause_ui, pause_time))
+        .add_systems(OnExit(State::Paused), (remove_pause_ui, unpause_time));
+    }
+}
+
+#[derive(Component)]
+struct PauseUI;
+
+fn pause_pressed(actions: Actions) -> bool {
+    actions.any_just_pressed(Action::Pause)
+}
+
+fn pause(mut nextstate: ResMut<NextState<State>>) {
+    nextstate.set(State::Paused);
+}
+
+fn resume(mut nextstate: ResMut<NextState<State>>) {
+    nextstate.set(State::Playing);
+}
+
+/// Gameplay only runs while [State::Playing], this also stops the clock for everything else.
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn create_pause_ui(mut commands: Commands) {
+    let menu = Menu::new("Paused")
+        .button("Resume", MenuAction::Resume)
+        .button("Restart", MenuAction::StartRun)
+        .button("Main menu", MenuAction::MainMenu);
+    // There is nothing to quit to in the browser.
+    #[cfg(not(target_arch = "wasm32"))]
+    let menu = menu.button("Quit", MenuAction::Quit);
+    menu.spawn(&mut commands, PauseUI);
+}
+
+fn remove_pause_ui(mut commands: Commands, pause_ui_entity: Option<Single<Entity, With<PauseUI>>>) {
+    if let Some(entity) = pause_ui_entity {
+        commands.entity(*entity).despawn_recursive();
+    }
+}
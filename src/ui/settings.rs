@@ -0,0 +1,112 @@
+use crate::gameplay::input::{DEFAULT_STICK_DEADZONE, StickDeadzone};
+use bevy::core_pipeline::bloom::Bloom;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::Path;
+
+/// The stick deadzones the settings page goes through.
+const STICK_DEADZONES: [f32; 6] = [0.05, 0.1, DEFAULT_STICK_DEADZONE, 0.2, 0.25, 0.3];
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>().add_systems(
+            Update,
+            (apply_bloom, apply_stick_deadzone).run_if(resource_changed::<Settings>),
+        );
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Startup, load_settings);
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct Settings {
+    /// Glow around the brightly colored sprites.
+    pub bloom: bool,
+    /// Applied as the [StickDeadzone] of every gamepad.
+    pub stick_deadzone: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            bloom: true,
+            stick_deadzone: DEFAULT_STICK_DEADZONE,
+        }
+    }
+}
+
+impl Settings {
+    /// Picks the next larger of the deadzones the settings page offers, going round to the
+    /// smallest after the largest.
+    pub fn next_stick_deadzone(&mut self) {
+        self.stick_deadzone = STICK_DEADZONES
+            .into_iter()
+            .find(|deadzone| *deadzone > self.stick_deadzone)
+            .unwrap_or(STICK_DEADZONES[0]);
+    }
+
+    pub fn load(path: &Path) -> Result<Settings, Box<dyn Error>> {
+        Ok(ron::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(
+            path,
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?,
+        )?;
+        Ok(())
+    }
+
+    /// Saves the settings so they survive a restart. Settings only last the session in the browser.
+    pub fn persist(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let path = settings_path();
+            if let Err(error) = self.save(&path) {
+                error!("Could not save settings to {}: {error}", path.display());
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn settings_path() -> std::path::PathBuf {
+    crate::storage::data_dir().join("settings.ron")
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn load_settings(mut settings: ResMut<Settings>) {
+    let path = settings_path();
+    if !path.exists() {
+        return;
+    }
+    match Settings::load(&path) {
+        Ok(loaded) => *settings = loaded,
+        Err(error) => error!("Could not load settings from {}: {error}", path.display()),
+    }
+}
+
+fn apply_bloom(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    cameras: Query<Entity, With<Camera2d>>,
+) {
+    for camera in cameras.iter() {
+        if settings.bloom {
+            commands.entity(camera).insert(Bloom::NATURAL);
+        } else {
+            commands.entity(camera).remove::<Bloom>();
+        }
+    }
+}
+
+fn apply_stick_deadzone(settings: Res<Settings>, mut deadzone: ResMut<StickDeadzone>) {
+    deadzone.0 = settings.stick_deadzone;
+}
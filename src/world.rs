@@ -1,15 +1,26 @@
-use crate::gameplay::player::PLAYER_SIZE;
 use bevy::prelude::*;
 
 pub const WORLD_SIZE: f32 = 1000.0;
-pub const WORLD_BOUNDARY_VECTOR: Vec3 = Vec3::new(
-    0.5 * WORLD_SIZE - 0.5 * PLAYER_SIZE,
-    0.5 * WORLD_SIZE - 0.5 * PLAYER_SIZE,
-    0.0,
-);
+
+/// How far from the center a square of `size` can be while staying fully inside the arena.
+pub fn arena_boundary(size: f32) -> Vec3 {
+    Vec3::new(
+        0.5 * WORLD_SIZE - 0.5 * size,
+        0.5 * WORLD_SIZE - 0.5 * size,
+        0.0,
+    )
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
 pub enum State {
     #[default]
+    /// The title screen, see [crate::ui::MenuPage] for its pages.
+    MainMenu,
+    /// Resets everything for a new run and moves on to [State::Playing] right away.
+    /// Run setup goes on `OnEnter(State::Starting)` so resuming from [State::Paused] keeps the run.
+    Starting,
     Playing,
+    /// The run is frozen until it is resumed or restarted.
+    Paused,
     GameOver,
 }
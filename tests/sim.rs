@@ -0,0 +1,280 @@
+use bevy::ecs::schedule::{ExecutorKind, LogLevel, ScheduleBuildSettings};
+use bevy::prelude::*;
+use bevy_creeps::gameplay::enemies::Enemy;
+use bevy_creeps::gameplay::input::PlayerInput;
+use bevy_creeps::gameplay::mode::GameMode;
+use bevy_creeps::gameplay::player::{MAX_PLAYERS, Player};
+use bevy_creeps::gameplay::power_ups::Shield;
+use bevy_creeps::gameplay::replay::{REPLAY_VERSION, Replay};
+use bevy_creeps::gameplay::ships::ShipSelection;
+use bevy_creeps::sim::{GameSim, GameSimError};
+use bevy_creeps::world::State;
+use std::f32::consts::PI;
+
+/// A single player on an empty arena that only gets the enemies a test spawns.
+fn quiet_sim() -> GameSim {
+    GameSim::builder()
+        .seed(1)
+        .random_spawning(false)
+        .build()
+        .unwrap()
+}
+
+/// How many of the enemies alive are `enemy`.
+fn count(sim: &mut GameSim, enemy: Enemy) -> usize {
+    sim.enemies()
+        .into_iter()
+        .filter(|(alive, _)| *alive == enemy)
+        .count()
+}
+
+#[test]
+fn cannon_kills_stationary_player() {
+    let mut sim = quiet_sim();
+    sim.spawn_enemy(Enemy::Cannon, Vec2::new(0.0, 200.0), Vec2::ZERO);
+
+    sim.step_seconds(3.0);
+
+    assert_eq!(sim.state(), State::GameOver);
+    assert_eq!(sim.cause_of_death(), Some(Enemy::Bullet));
+}
+
+#[test]
+fn rocket_ship_kills_stationary_player() {
+    let mut sim = quiet_sim();
+    sim.spawn_enemy(Enemy::RocketShip, Vec2::new(500.0, 0.0), Vec2::ZERO);
+
+    sim.step_seconds(2.5);
+    assert_eq!(count(&mut sim, Enemy::Rocket), 0);
+
+    sim.step_seconds(3.0);
+    assert_eq!(sim.state(), State::GameOver);
+    assert_eq!(sim.cause_of_death(), Some(Enemy::Rocket));
+}
+
+#[test]
+fn splitter_breaks_into_shards() {
+    let mut sim = quiet_sim();
+    sim.spawn_enemy(Enemy::Splitter, Vec2::new(-300.0, 400.0), Vec2::X);
+
+    sim.step_seconds(2.5);
+    assert_eq!(count(&mut sim, Enemy::Splitter), 1);
+    assert_eq!(count(&mut sim, Enemy::Shard), 0);
+
+    sim.step_seconds(1.0);
+    assert_eq!(count(&mut sim, Enemy::Splitter), 0);
+    assert_eq!(count(&mut sim, Enemy::Shard), 4);
+}
+
+#[test]
+fn mine_layer_drops_mines_along_its_path() {
+    let mut sim = quiet_sim();
+    sim.spawn_enemy(Enemy::MineLayer, Vec2::new(-300.0, 300.0), Vec2::X);
+
+    sim.step_seconds(3.5);
+
+    let mines: Vec<Vec2> = sim
+        .enemies()
+        .into_iter()
+        .filter(|(enemy, _)| *enemy == Enemy::Mine)
+        .map(|(_, position)| position)
+        .collect();
+    assert_eq!(mines.len(), 3);
+    assert!(mines.iter().all(|mine| (mine.y - 300.0).abs() < 1.0));
+    assert_eq!(sim.state(), State::Playing);
+}
+
+#[test]
+fn mine_only_kills_once_armed() {
+    let mut sim = quiet_sim();
+    sim.spawn_enemy(Enemy::Mine, Vec2::ZERO, Vec2::ZERO);
+
+    sim.step_seconds(0.5);
+    assert_eq!(sim.state(), State::Playing);
+
+    sim.step_seconds(1.0);
+    assert_eq!(sim.state(), State::GameOver);
+    assert_eq!(sim.cause_of_death(), Some(Enemy::Mine));
+}
+
+#[test]
+fn orbiter_circles_in_on_player() {
+    let mut sim = quiet_sim();
+    sim.spawn_enemy(Enemy::Orbiter, Vec2::new(350.0, 0.0), Vec2::Y);
+
+    // Half a turn in, a hundred closer.
+    sim.step_seconds(4.0);
+    let (_, orbiter) = sim.enemies()[0];
+    assert!(orbiter.distance(Vec2::new(-250.0, 0.0)) < 20.0, "{orbiter}");
+    assert_eq!(sim.state(), State::Playing);
+
+    sim.step_seconds(12.0);
+    assert_eq!(sim.state(), State::GameOver);
+    assert_eq!(sim.cause_of_death(), Some(Enemy::Orbiter));
+}
+
+#[test]
+fn onslaught_closes_a_ring_of_cannons_in_after_a_minute() {
+    let mut sim = GameSim::builder()
+        .seed(1)
+        .mode(GameMode::Onslaught)
+        .build()
+        .unwrap();
+    // Keep the player alive by clearing out the random spawns before they get close.
+    for _ in 0..59 {
+        sim.step_seconds(1.0);
+        let world = sim.app_mut().world_mut();
+        let enemies: Vec<Entity> = world
+            .query_filtered::<Entity, With<Enemy>>()
+            .iter(world)
+            .collect();
+        for enemy in enemies {
+            world.despawn(enemy);
+        }
+    }
+    assert_eq!(count(&mut sim, Enemy::Cannon), 0);
+
+    sim.step_seconds(1.5);
+
+    assert_eq!(sim.state(), State::Playing);
+    assert!(count(&mut sim, Enemy::Cannon) >= 8);
+}
+
+/// Plays a run with scripted input and records what happened every second.
+fn play(seed: u64) -> Vec<(State, u32, Option<Vec2>)> {
+    let mut sim = GameSim::builder().seed(seed).build().unwrap();
+    let mut record = Vec::new();
+    for second in 0..60 {
+        let angle = second as f32 * 1.3;
+        sim.set_input(Vec2::from_angle(angle));
+        sim.set_dash(second % 4 == 0);
+        sim.step_seconds(1.0);
+        record.push((sim.state(), sim.score(), sim.player_position()));
+        if sim.state() == State::GameOver {
+            break;
+        }
+    }
+    record
+}
+
+#[test]
+fn same_seed_plays_the_same_run() {
+    for seed in [3, 7, 11] {
+        assert_eq!(play(seed), play(seed), "seed {seed} played out differently");
+    }
+}
+
+#[test]
+fn gameplay_systems_run_in_a_fixed_order() {
+    let mut sim = GameSim::builder().seed(1).build().unwrap();
+    sim.app_mut().edit_schedule(FixedUpdate, |schedule| {
+        schedule.set_build_settings(ScheduleBuildSettings {
+            ambiguity_detection: LogLevel::Error,
+            ..default()
+        });
+    });
+
+    // Building the schedule fails if systems that touch the same data have no order.
+    sim.step(1);
+}
+
+#[test]
+fn player_count_out_of_range_is_an_error() {
+    for players in [0, MAX_PLAYERS + 1] {
+        assert!(matches!(
+            GameSim::builder().players(players).build(),
+            Err(GameSimError::PlayerCount(count)) if count == players
+        ));
+    }
+}
+
+/// Two players circling in opposite directions, dashing and firing shockwaves now and then.
+fn circling_replay(seed: u64) -> Replay {
+    let inputs = (0..40 * 64)
+        .map(|tick| {
+            (0..2)
+                .map(|player| PlayerInput {
+                    movement: Vec2::from_angle(tick as f32 * 0.02 + player as f32 * PI),
+                    dash: tick % 200 < 5,
+                    shockwave: tick % 640 < 5,
+                    ..default()
+                })
+                .collect()
+        })
+        .collect();
+    Replay {
+        version: REPLAY_VERSION,
+        seed,
+        mode: GameMode::Classic,
+        players: 2,
+        ships: ShipSelection::default(),
+        wave_script: None,
+        inputs,
+    }
+}
+
+/// Plays `replay` to its end on the multi-threaded executor and returns where everything ended up.
+fn play_replay(replay: &Replay) -> (State, u32, Vec<Vec2>, Vec<(Enemy, Vec2)>) {
+    let mut sim = GameSim::builder().replay(replay.clone()).build().unwrap();
+    sim.app_mut().edit_schedule(FixedUpdate, |schedule| {
+        schedule.set_executor_kind(ExecutorKind::MultiThreaded);
+    });
+    sim.step(replay.inputs.len() as u32);
+    (
+        sim.state(),
+        sim.score(),
+        sim.player_positions(),
+        sim.enemies(),
+    )
+}
+
+#[test]
+fn replay_plays_the_same_run_on_the_multi_threaded_executor() {
+    let replay = circling_replay(5);
+    assert_eq!(play_replay(&replay), play_replay(&replay));
+}
+
+/// Gives every player a shield.
+fn shield_players(sim: &mut GameSim) {
+    let world = sim.app_mut().world_mut();
+    let players: Vec<Entity> = world
+        .query_filtered::<Entity, With<Player>>()
+        .iter(world)
+        .collect();
+    for player in players {
+        world.entity_mut(player).insert(Shield);
+    }
+}
+
+fn player_has_shield(sim: &mut GameSim) -> bool {
+    let world = sim.app_mut().world_mut();
+    world
+        .query_filtered::<(), (With<Player>, With<Shield>)>()
+        .iter(world)
+        .next()
+        .is_some()
+}
+
+#[test]
+fn shield_survives_laser_beam() {
+    let mut sim = quiet_sim();
+    shield_players(&mut sim);
+    sim.spawn_enemy(Enemy::LaserSweeper, Vec2::new(700.0, 0.0), Vec2::NEG_X);
+
+    sim.step_seconds(6.0);
+
+    assert_eq!(sim.state(), State::Playing);
+    assert!(!player_has_shield(&mut sim));
+}
+
+#[test]
+fn shield_survives_boss() {
+    let mut sim = quiet_sim();
+    shield_players(&mut sim);
+    sim.spawn_enemy(Enemy::Boss, Vec2::ZERO, Vec2::NEG_Y);
+
+    sim.step_seconds(1.0);
+
+    assert_eq!(sim.state(), State::Playing);
+    assert!(!player_has_shield(&mut sim));
+}